@@ -1,28 +1,32 @@
 use std::collections::HashMap;
 
+use advent_of_code::parse::{Input, ParseError};
+
 advent_of_code::solution!(1);
 
-struct Input {
+struct Lists {
     left: Vec<u32>,
     right: Vec<u32>,
 }
 
-impl From<&str> for Input {
-    fn from(value: &str) -> Self {
-        let (left, right) = value
+impl Lists {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let pairs = input
+            .as_str()
             .lines()
-            .flat_map(|line| line.split_once("   "))
-            .map(|(l, r)| (l.parse::<u32>().unwrap(), r.parse::<u32>().unwrap()))
-            .unzip();
-        Self { left, right }
+            .map(|line| input.ints_n(line))
+            .collect::<Result<Vec<[u32; 2]>, _>>()?;
+        let (left, right) = pairs.into_iter().map(|[l, r]| (l, r)).unzip();
+        Ok(Self { left, right })
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let Input {
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let Lists {
         mut left,
         mut right,
-    } = Input::from(input);
+    } = Lists::parse(input)?;
 
     left.sort();
     right.sort();
@@ -32,11 +36,11 @@ pub fn part_one(input: &str) -> Option<u32> {
         .zip(right.iter())
         .map(|(l, r)| l.abs_diff(*r))
         .sum();
-    Some(ans)
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let Input { left, right } = Input::from(input);
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let Lists { left, right } = Lists::parse(input)?;
 
     let mut count = HashMap::new();
     for n in &right {
@@ -44,7 +48,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     }
 
     let ans = left.iter().map(|n| n * count.get(n).unwrap_or(&0)).sum();
-    Some(ans)
+    Ok(ans)
 }
 
 #[cfg(test)]
@@ -54,12 +58,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11));
+        assert_eq!(result, Ok(11));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(31));
+        assert_eq!(result, Ok(31));
     }
 }
@@ -1,15 +1,6 @@
-advent_of_code::solution!(2);
-
-struct Input {
-    reports: Vec<Report>,
-}
+use advent_of_code::parse::{Input, ParseError};
 
-impl From<&str> for Input {
-    fn from(value: &str) -> Self {
-        let reports = value.lines().map(Report::from).collect();
-        Self { reports }
-    }
-}
+advent_of_code::solution!(2);
 
 struct Report(Vec<i32>);
 
@@ -31,17 +22,25 @@ impl Report {
     }
 }
 
-impl From<&str> for Report {
-    fn from(value: &str) -> Self {
-        let values = value
+impl Report {
+    fn parse(input: &Input, line: &str) -> Result<Self, ParseError> {
+        let values = line
             .split(' ')
-            .map(|n| n.parse())
-            .collect::<Result<_, _>>()
-            .unwrap();
-        Self(values)
+            .map(|n| input.parse(n))
+            .collect::<Result<_, _>>()?;
+        Ok(Self(values))
     }
 }
 
+fn reports(input: &str) -> Result<Vec<Report>, ParseError> {
+    let input = Input::new(input);
+    input
+        .as_str()
+        .lines()
+        .map(|line| Report::parse(&input, line))
+        .collect()
+}
+
 fn safe_sequence(values: &[i32]) -> bool {
     let diffed_values = values
         .iter()
@@ -65,19 +64,17 @@ fn safe_sequence(values: &[i32]) -> bool {
     true
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let Input { reports } = input.into();
-    let ans = reports.iter().filter(|r| r.is_safe()).count();
-    Some(ans)
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let ans = reports(input)?.iter().filter(|r| r.is_safe()).count();
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let Input { reports } = input.into();
-    let ans = reports
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let ans = reports(input)?
         .iter()
         .filter(|r| r.is_safe_with_dampening())
         .count();
-    Some(ans)
+    Ok(ans)
 }
 
 #[cfg(test)]
@@ -87,12 +84,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2));
+        assert_eq!(result, Ok(2));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4));
+        assert_eq!(result, Ok(4));
     }
 }
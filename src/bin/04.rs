@@ -1,9 +1,11 @@
-use ndarray::{array, s, Array2};
+use advent_of_code::grid::Grid;
+use advent_of_code::parse::{Input, ParseError};
+use ndarray::{array, s};
 
 advent_of_code::solution!(4);
 
 struct Map {
-    map: Array2<char>,
+    map: Grid<char>,
 }
 
 impl Map {
@@ -58,24 +60,19 @@ impl Map {
     }
 }
 
-impl From<&str> for Map {
-    fn from(s: &str) -> Self {
-        let lines: Vec<_> = s.lines().collect();
-        let shape = (lines.len(), lines[0].len());
-        let vec = lines.into_iter().flat_map(|l| l.chars()).collect();
-        let map = Array2::from_shape_vec(shape, vec).unwrap();
-        Self { map }
+impl Map {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::try_parse(&Input::new(input), input, |c| c)?;
+        Ok(Self { map })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    Some(map.total_xmas())
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(Map::parse(input)?.total_xmas())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    Some(map.total_max_cross())
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(Map::parse(input)?.total_max_cross())
 }
 
 #[cfg(test)]
@@ -85,12 +82,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(18));
+        assert_eq!(result, Ok(18));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(9));
+        assert_eq!(result, Ok(9));
     }
 }
@@ -1,91 +1,91 @@
-use std::{cmp::Ordering, collections::HashSet};
+use advent_of_code::{
+    graph::{BitSet, Graph, NodeId},
+    parse::{Input, ParseError},
+};
 
 advent_of_code::solution!(5);
 
-#[derive(Clone)]
-struct Update {
-    pages: Vec<u32>,
+struct Update<'a> {
+    line: &'a str,
+    pages: Vec<NodeId>,
 }
 
-impl Update {
-    fn is_sorted(&self, rules: &HashSet<(u32, u32)>) -> bool {
-        self.pages
-            .is_sorted_by(|&lhs, &rhs| rules.contains(&(lhs, rhs)) || !rules.contains(&(rhs, lhs)))
+impl Update<'_> {
+    /// Whether no rule puts a page after one that comes later in the update. Pages that no
+    /// rule relates may come in any order.
+    fn is_ordered(&self, rules: &Graph<u32>) -> bool {
+        self.pages.iter().enumerate().all(|(i, &before)| {
+            self.pages[i + 1..]
+                .iter()
+                .all(|&after| !rules.has_edge(after, before))
+        })
     }
 
-    fn sort(&mut self, rules: &HashSet<(u32, u32)>) {
-        self.pages.sort_by(|&lhs, &rhs| {
-            if rules.contains(&(lhs, rhs)) {
-                Ordering::Less
-            } else if rules.contains(&(rhs, lhs)) {
-                Ordering::Greater
-            } else {
-                Ordering::Equal
-            }
-        });
+    /// The pages in the order required by the rules that mention two pages of this update.
+    fn sorted(&self, input: &Input, rules: &Graph<u32>) -> Result<Vec<NodeId>, ParseError> {
+        let pages: BitSet = self.pages.iter().copied().collect();
+        rules
+            .toposort_within(&pages)
+            .map_err(|cycle| input.error(self.line, format!("rules cannot order update: {cycle}")))
     }
+}
 
-    fn middle(&self) -> u32 {
-        self.pages[self.pages.len() / 2]
-    }
+struct Protocol<'a> {
+    rules: Graph<u32>,
+    updates: Vec<Update<'a>>,
 }
 
-impl From<&str> for Update {
-    fn from(value: &str) -> Self {
-        let pages = value.split(',').map(|n| n.parse().unwrap()).collect();
-        Self { pages }
+impl<'a> Protocol<'a> {
+    fn middle(&self, pages: &[NodeId]) -> u32 {
+        *self.rules.label(pages[pages.len() / 2])
     }
-}
 
-struct Protocol {
-    rules: HashSet<(u32, u32)>,
-    updates: Vec<Update>,
-}
+    fn parse(input: &Input<'a>) -> Result<Self, ParseError> {
+        let mut sections = input.sections();
+        let (rules_str, updates_str) = match (sections.next(), sections.next()) {
+            (Some(rules), Some(updates)) => (rules, updates),
+            _ => return Err(input.error(input.as_str(), "expected rules and updates")),
+        };
 
-impl From<&str> for Protocol {
-    fn from(value: &str) -> Self {
-        let (rules_str, updates_str) = value.split_once("\n\n").unwrap();
-        let rules = rules_str
+        let mut rules = Graph::directed();
+        for line in rules_str.lines() {
+            let (before, after) = input.split_once(line, "|")?;
+            rules.add_edge(input.parse(before)?, input.parse(after)?);
+        }
+        let updates = updates_str
             .lines()
             .map(|line| {
-                let (l, r) = line.split_once('|').unwrap();
-                (l.parse().unwrap(), r.parse().unwrap())
+                let pages = line
+                    .split(',')
+                    .map(|page| Ok(rules.add_node(input.parse(page)?)))
+                    .collect::<Result<_, ParseError>>()?;
+                Ok(Update { line, pages })
             })
-            .collect();
-        let updates = updates_str.lines().map(Update::from).collect();
-        Self { rules, updates }
+            .collect::<Result<_, ParseError>>()?;
+        Ok(Self { rules, updates })
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let protocol: Protocol = input.into();
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let protocol = Protocol::parse(&Input::new(input))?;
     let ans = protocol
         .updates
         .iter()
-        .filter_map(|update| {
-            if update.is_sorted(&protocol.rules) {
-                Some(update.middle())
-            } else {
-                None
-            }
-        })
+        .filter(|update| update.is_ordered(&protocol.rules))
+        .map(|update| protocol.middle(&update.pages))
         .sum();
-    Some(ans)
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let protocol: Protocol = input.into();
-    let ans = protocol
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let input = Input::new(input);
+    let protocol = Protocol::parse(&input)?;
+    protocol
         .updates
         .iter()
-        .filter(|update| !update.is_sorted(&protocol.rules))
-        .cloned()
-        .map(|mut update| {
-            update.sort(&protocol.rules);
-            update.middle()
-        })
-        .sum();
-    Some(ans)
+        .filter(|update| !update.is_ordered(&protocol.rules))
+        .map(|update| Ok(protocol.middle(&update.sorted(&input, &protocol.rules)?)))
+        .sum()
 }
 
 #[cfg(test)]
@@ -95,12 +95,22 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+        assert_eq!(result, Ok(143));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+        assert_eq!(result, Ok(123));
+    }
+
+    #[test]
+    fn test_partial_rules() {
+        // no rule orders 97, so the update is correct as it is.
+        assert_eq!(part_one("47|53\n\n97,47,53"), Ok(47));
+        assert_eq!(part_two("47|53\n\n97,47,53"), Ok(0));
+        assert_eq!(part_one("47|53\n\n53,97,47"), Ok(0));
+        // the rules contradict each other, so no order satisfies them.
+        assert!(part_two("1|2\n2|1\n\n1,2").is_err());
     }
 }
@@ -1,45 +1,22 @@
-use std::{collections::HashSet, hash::Hash};
-
-use ndarray::Array2;
+use advent_of_code::{
+    cycle,
+    geom::{Coord, Dir4},
+    grid::{BitGrid, Grid},
+    parse::{Input, ParseError},
+};
 
 advent_of_code::solution!(6);
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn rotate_right_90(&self) -> Self {
-        match *self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-}
-
-#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 struct Person {
-    pos: (usize, usize),
-    dir: Direction,
+    pos: Coord,
+    dir: Dir4,
 }
 
 impl Person {
-    fn ahead(&self, map: &Map) -> Option<(usize, usize)> {
-        let (max_y, max_x) = map.shape();
-        let (y, x) = self.pos;
-        match self.dir {
-            Direction::Up if y > 0 => Some((y - 1, x)),
-            Direction::Down if y < max_y - 1 => Some((y + 1, x)),
-            Direction::Left if x > 0 => Some((y, x - 1)),
-            Direction::Right if x < max_x - 1 => Some((y, x + 1)),
-            _ => None,
-        }
+    fn ahead(&self, map: &Map) -> Option<Coord> {
+        let ahead = self.pos.step(self.dir);
+        map.tiles.contains(ahead).then_some(ahead)
     }
 
     fn walk(&mut self, map: &Map) -> bool {
@@ -47,92 +24,85 @@ impl Person {
             return false;
         };
         if map.tiles[ahead] != '.' {
-            self.dir = self.dir.rotate_right_90();
+            self.dir = self.dir.turn_right();
         } else {
             self.pos = ahead;
         }
         true
     }
+
+    fn walks_in_loop(self, map: &Map) -> bool {
+        // leaving the map ends in `None`, which steps to itself. That is the only cycle of length
+        // 1, as a guard on the map always moves or turns.
+        let length = cycle::cycle_length(Some(self), |guard| {
+            guard.and_then(|mut guard| guard.walk(map).then_some(guard))
+        });
+        length > 1
+    }
 }
 
 struct Map {
-    tiles: Array2<char>,
+    tiles: Grid<char>,
 }
 
 impl Map {
-    fn shape(&self) -> (usize, usize) {
-        let shape = self.tiles.shape();
-        (shape[0], shape[1])
+    fn bits(&self) -> BitGrid {
+        BitGrid::new(self.tiles.width(), self.tiles.height())
     }
 }
 
-struct Input {
+struct Lab {
     map: Map,
     guard: Person,
 }
 
-impl From<&str> for Input {
-    fn from(value: &str) -> Self {
-        let chars: Vec<_> = value.lines().flat_map(|line| line.chars()).collect();
-        let height = value.lines().count();
-        let width = chars.len() / height;
-        let mut tiles = Array2::from_shape_vec((height, width), chars).unwrap();
-        let mut pos = (0, 0);
-        for (coord, tile) in tiles.indexed_iter_mut() {
-            if *tile == '^' {
-                pos = coord;
-                *tile = '.';
-                break;
-            }
-        }
+impl Lab {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let mut tiles = Grid::try_parse(&input, input.as_str(), |c| c)?;
+        let pos = tiles
+            .find(&'^')
+            .ok_or_else(|| input.error(input.as_str(), "expected a guard `^` on the map"))?;
+        tiles[pos] = '.';
 
         let map = Map { tiles };
         let guard = Person {
             pos,
-            dir: Direction::Up,
+            dir: Dir4::North,
         };
 
-        Self { map, guard }
+        Ok(Self { map, guard })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let Input { map, mut guard } = input.into();
-    let mut visited = HashSet::new();
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let Lab { map, mut guard } = Lab::parse(input)?;
+    let mut visited = map.bits();
     visited.insert(guard.pos);
     while guard.walk(&map) {
         visited.insert(guard.pos);
     }
-    Some(visited.len())
+    Ok(visited.len())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let Input { mut map, mut guard } = input.into();
-    let mut blacklist = HashSet::new();
-    let mut visited = HashSet::new();
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let Lab { mut map, mut guard } = Lab::parse(input)?;
+    let mut blacklist = map.bits();
     let mut looped = 0;
     while let Some(ahead) = guard.ahead(&map) {
         blacklist.insert(guard.pos);
-        visited.insert(guard);
 
         if map.tiles[ahead] == '.' && !blacklist.contains(&ahead) {
             map.tiles[ahead] = 'O';
-
-            let mut guard2 = guard;
-            let mut visited2 = visited.clone();
-            'time: while guard2.walk(&map) {
-                if !visited2.insert(guard2) {
-                    looped += 1;
-                    break 'time;
-                }
+            if guard.walks_in_loop(&map) {
+                looped += 1;
             }
-
             map.tiles[ahead] = '.';
         }
 
         guard.walk(&map);
     }
-    Some(looped)
+    Ok(looped)
 }
 
 #[cfg(test)]
@@ -142,12 +112,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(41));
+        assert_eq!(result, Ok(41));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
+        assert_eq!(result, Ok(6));
     }
 }
@@ -1,11 +1,11 @@
-advent_of_code::solution!(7);
+use advent_of_code::{
+    math::concat_digits,
+    parse::{Input, ParseError},
+};
 
-fn concat(lhs: usize, rhs: usize) -> usize {
-    let pad = (rhs as f64 + 0.5).log10().ceil() as u32;
-    lhs * 10_usize.pow(pad) + rhs
-}
+advent_of_code::solution!(7);
 
-fn calculate(target: usize, lhs: usize, values: &[usize], ops: &[Operator]) -> bool {
+fn calculate(target: u64, lhs: u64, values: &[u64], ops: &[Operator]) -> bool {
     if values.is_empty() {
         return target == lhs;
     }
@@ -16,7 +16,9 @@ fn calculate(target: usize, lhs: usize, values: &[usize], ops: &[Operator]) -> b
     ops.iter().any(|op| match op {
         Operator::Plus => calculate(target, lhs + rhs, values, ops),
         Operator::Multiply => calculate(target, lhs * rhs, values, ops),
-        Operator::Concat => calculate(target, concat(lhs, rhs), values, ops),
+        Operator::Concat => {
+            concat_digits(lhs, rhs).is_some_and(|lhs| calculate(target, lhs, values, ops))
+        }
     })
 }
 
@@ -28,12 +30,12 @@ enum Operator {
 }
 
 struct Equation {
-    value: usize,
-    tokens: Vec<usize>,
+    value: u64,
+    tokens: Vec<u64>,
 }
 
 impl Equation {
-    fn calibrate(&self) -> Option<usize> {
+    fn calibrate(&self) -> Option<u64> {
         const OPS: &[Operator] = &[Operator::Plus, Operator::Multiply];
         if calculate(self.value, self.tokens[0], &self.tokens[1..], OPS) {
             Some(self.value)
@@ -42,7 +44,7 @@ impl Equation {
         }
     }
 
-    fn calibrate_with_concat(&self) -> Option<usize> {
+    fn calibrate_with_concat(&self) -> Option<u64> {
         const OPS: &[Operator] = &[Operator::Plus, Operator::Multiply, Operator::Concat];
         if calculate(self.value, self.tokens[0], &self.tokens[1..], OPS) {
             Some(self.value)
@@ -52,35 +54,44 @@ impl Equation {
     }
 }
 
-impl From<&str> for Equation {
-    fn from(value: &str) -> Self {
-        let (value_str, tokens_str) = value.split_once(':').unwrap();
-        let value = value_str.parse().unwrap();
+impl Equation {
+    fn parse(input: &Input, line: &str) -> Result<Self, ParseError> {
+        let (value_str, tokens_str) = input.split_once(line, ":")?;
+        let value = input.parse(value_str)?;
         let tokens = tokens_str
             .trim()
             .split(' ')
-            .map(|n| n.parse().unwrap())
-            .collect();
-        Self { value, tokens }
+            .map(|n| input.parse(n))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { value, tokens })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let equations: Vec<_> = input.lines().map(Equation::from).collect();
+fn equations(input: &str) -> Result<Vec<Equation>, ParseError> {
+    let input = Input::new(input);
+    input
+        .as_str()
+        .lines()
+        .map(|line| Equation::parse(&input, line))
+        .collect()
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let equations = equations(input)?;
     let ans = equations.iter().filter_map(|eq| eq.calibrate()).sum();
     // panic!("awdwa");
-    Some(ans)
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let equations: Vec<_> = input.lines().map(Equation::from).collect();
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let equations = equations(input)?;
     let ans = equations
         .iter()
         .filter_map(|eq| eq.calibrate_with_concat())
         .sum();
 
     // panic!("awdwa");
-    Some(ans)
+    Ok(ans)
 }
 
 #[cfg(test)]
@@ -90,12 +101,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3749));
+        assert_eq!(result, Ok(3749));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11387));
+        assert_eq!(result, Ok(11387));
     }
 }
@@ -1,21 +1,23 @@
 use std::collections::{HashMap, HashSet};
 
-use ndarray::Array2;
+use advent_of_code::{
+    geom::Coord,
+    grid::Grid,
+    parse::{Input, ParseError},
+};
 
 advent_of_code::solution!(8);
 
-type Coord = (isize, isize);
-
 struct Map {
-    tiles: Array2<char>,
+    tiles: Grid<char>,
     antennas: HashMap<char, Vec<Coord>>,
 }
 
 impl Map {
-    fn new(tiles: Array2<char>) -> Self {
+    fn new(tiles: Grid<char>) -> Self {
         let mut antennas = HashMap::new();
-        for (pos, &tile) in tiles.indexed_iter() {
-            let pos = (pos.0 as isize, pos.1 as isize);
+        for pos in tiles.coords() {
+            let tile = tiles[pos];
             if tile != '.' {
                 antennas
                     .entry(tile)
@@ -27,12 +29,7 @@ impl Map {
     }
 
     fn valid_coord(&self, coord: &Coord) -> bool {
-        if coord.0 >= 0 && coord.1 >= 0 {
-            let coord = (coord.0 as usize, coord.1 as usize);
-            self.tiles.get(coord).is_some()
-        } else {
-            false
-        }
+        self.tiles.contains(*coord)
     }
 
     fn antinodes(&self, repeating: bool) -> usize {
@@ -43,19 +40,15 @@ impl Map {
                 .flat_map(|&pos0| coords.iter().map(move |&pos1| (pos0, pos1)))
                 .filter(|(pos0, pos1)| pos0 != pos1)
             {
-                let (y0, x0) = pos0;
-                let (y1, x1) = pos1;
-                let y_diff = y1 - y0;
-                let x_diff = x1 - x0;
+                let diff = pos1 - pos0;
                 if repeating {
                     let mut curr_pos = pos1;
                     while self.valid_coord(&curr_pos) {
                         total.insert(curr_pos);
-                        curr_pos.0 += y_diff;
-                        curr_pos.1 += x_diff;
+                        curr_pos += diff;
                     }
                 } else {
-                    let new_pos = (y1 + y_diff, x1 + x_diff);
+                    let new_pos = pos1 + diff;
                     if self.valid_coord(&new_pos) {
                         total.insert(new_pos);
                     }
@@ -66,27 +59,24 @@ impl Map {
     }
 }
 
-impl From<&str> for Map {
-    fn from(value: &str) -> Self {
-        let chars: Vec<_> = value.lines().flat_map(|line| line.chars()).collect();
-        let height = value.lines().count();
-        let width = chars.len() / height;
-        let shape = (height, width);
-        let tiles = Array2::from_shape_vec(shape, chars).unwrap();
-        Self::new(tiles)
+impl Map {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self::new(Grid::try_parse(
+            &Input::new(input),
+            input,
+            |c| c,
+        )?))
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    let ans = map.antinodes(false);
-    Some(ans)
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let ans = Map::parse(input)?.antinodes(false);
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    let ans = map.antinodes(true);
-    Some(ans)
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let ans = Map::parse(input)?.antinodes(true);
+    Ok(ans)
 }
 
 #[cfg(test)]
@@ -96,12 +86,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(14));
+        assert_eq!(result, Ok(14));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(34));
+        assert_eq!(result, Ok(34));
     }
 }
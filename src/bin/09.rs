@@ -1,4 +1,11 @@
-advent_of_code::solution!(9);
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use advent_of_code::parse::{Input, ParseError};
+
+advent_of_code::solution!(9; "--show" => show);
+
+/// Larger disks take too much room to show.
+const MAX_SHOWN_BLOCKS: usize = 200;
 
 #[derive(Copy, Clone, Debug)]
 struct Block {
@@ -11,6 +18,8 @@ struct Block {
 struct DiskMap {
     file_blocks: Vec<Block>,
     empty_blocks: Vec<Block>,
+    /// Number of blocks of the whole disk, including the free ones.
+    len: usize,
 }
 
 impl DiskMap {
@@ -53,27 +62,46 @@ impl DiskMap {
         Self {
             file_blocks,
             empty_blocks: Vec::new(),
+            len: self.len,
         }
     }
 
     fn compact2(&self) -> Self {
+        self.compact2_with(|_| {})
+    }
+
+    /// Moves every file, highest id first, to the leftmost free span that fits it, calling
+    /// `on_move` with the files after each move. The free spans are kept in one min-heap of
+    /// positions per size, so finding that span takes a look at the top of at most 9 heaps.
+    fn compact2_with(&self, mut on_move: impl FnMut(&[Block])) -> Self {
         let mut file_blocks = self.file_blocks.clone();
-        let mut empty_blocks = self.empty_blocks.clone();
-
-        for file in file_blocks.iter_mut().rev() {
-            for empty in empty_blocks.iter_mut() {
-                if empty.size >= file.size && file.pos > empty.pos {
-                    file.pos = empty.pos;
-                    empty.size -= file.size;
-                    empty.pos += file.size;
-                    break;
-                }
+        let mut free: [BinaryHeap<Reverse<usize>>; 10] = Default::default();
+        for empty in self.empty_blocks.iter().filter(|empty| empty.size > 0) {
+            free[empty.size].push(Reverse(empty.pos));
+        }
+
+        // the space a file leaves behind is right of every file still to move, so it is never
+        // used again.
+        for i in (0..file_blocks.len()).rev() {
+            let file = file_blocks[i];
+            let leftmost = (file.size..free.len())
+                .filter_map(|size| free[size].peek().map(|&Reverse(pos)| (pos, size)))
+                .min();
+            let Some((pos, size)) = leftmost.filter(|&(pos, _)| pos < file.pos) else {
+                continue;
+            };
+            free[size].pop();
+            if size > file.size {
+                free[size - file.size].push(Reverse(pos + file.size));
             }
+            file_blocks[i].pos = pos;
+            on_move(&file_blocks);
         }
 
         Self {
             file_blocks,
-            empty_blocks,
+            empty_blocks: Vec::new(),
+            len: self.len,
         }
     }
 
@@ -83,16 +111,36 @@ impl DiskMap {
             .map(|&Block { id, pos, size }| (pos..pos + size).sum::<usize>() * id)
             .sum()
     }
+
+    fn render(&self) -> String {
+        render(&self.file_blocks, self.len)
+    }
 }
 
-impl From<&str> for DiskMap {
-    fn from(value: &str) -> Self {
+/// The layout as in the puzzle's walkthrough, `.` for free blocks and the last digit of the id
+/// for files.
+fn render(file_blocks: &[Block], len: usize) -> String {
+    let mut blocks = vec!['.'; len];
+    for file in file_blocks {
+        let digit = char::from_digit((file.id % 10) as u32, 10).unwrap();
+        blocks[file.pos..file.pos + file.size].fill(digit);
+    }
+    blocks.into_iter().collect()
+}
+
+impl DiskMap {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let text = input.as_str().trim_end();
         let mut file_blocks = Vec::new();
         let mut empty_blocks = Vec::new();
         let mut pos = 0;
-        for (i, c) in value.as_bytes().iter().enumerate() {
+        for (i, c) in text.char_indices() {
             let id = i / 2;
-            let size = (c - b'0') as usize;
+            let size = c
+                .to_digit(10)
+                .ok_or_else(|| input.error(&text[i..], "expected a digit"))?
+                as usize;
             if i % 2 == 0 {
                 file_blocks.push(Block { id, pos, size });
             } else {
@@ -101,23 +149,45 @@ impl From<&str> for DiskMap {
             pos += size;
         }
 
-        Self {
+        Ok(Self {
             file_blocks,
             empty_blocks,
-        }
+            len: pos,
+        })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let disk: DiskMap = input.into();
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let disk = DiskMap::parse(input)?;
     let ans = disk.compact().checksum();
-    Some(ans)
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let disk: DiskMap = input.into();
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let disk = DiskMap::parse(input)?;
     let ans = disk.compact2().checksum();
-    Some(ans)
+    Ok(ans)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Prints the disk after every file moved for part two, and after compacting for part one.
+fn show(input: &str) {
+    let disk = match DiskMap::parse(input) {
+        Ok(disk) => disk,
+        Err(err) => return eprintln!("{err}"),
+    };
+    if disk.len > MAX_SHOWN_BLOCKS {
+        return eprintln!(
+            "the disk has {} blocks, only disks of up to {MAX_SHOWN_BLOCKS} are shown",
+            disk.len
+        );
+    }
+
+    println!("{}\n", disk.render());
+    println!("part one:\n{}\n", disk.compact().render());
+    println!("part two:");
+    disk.compact2_with(|file_blocks| println!("{}", render(file_blocks, disk.len)));
 }
 
 #[cfg(test)]
@@ -127,12 +197,34 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(1928));
+        assert_eq!(result, Ok(1928));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2858));
+        assert_eq!(result, Ok(2858));
+    }
+
+    #[test]
+    fn test_walkthrough() {
+        let disk = DiskMap::parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(disk.render(), "00...111...2...333.44.5555.6666.777.888899");
+        assert_eq!(
+            disk.compact().render(),
+            "0099811188827773336446555566.............."
+        );
+
+        let mut steps = Vec::new();
+        disk.compact2_with(|file_blocks| steps.push(render(file_blocks, disk.len)));
+        assert_eq!(
+            steps,
+            [
+                "0099.111...2...333.44.5555.6666.777.8888..",
+                "0099.1117772...333.44.5555.6666.....8888..",
+                "0099.111777244.333....5555.6666.....8888..",
+                "00992111777.44.333....5555.6666.....8888..",
+            ]
+        );
     }
 }
@@ -1,48 +1,24 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
-use ndarray::Array2;
+use advent_of_code::{
+    geom::Coord,
+    grid::{BitGrid, Grid},
+    parse::{Input, ParseError},
+};
 
 advent_of_code::solution!(10);
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Coord(isize, isize);
-
-impl Coord {
-    fn as_sized(&self) -> Option<(usize, usize)> {
-        if self.0 < 0 || self.1 < 0 {
-            None
-        } else {
-            Some((self.0 as usize, self.1 as usize))
-        }
-    }
-
-    fn neighbors(&self) -> [Self; 4] {
-        [
-            Self(self.0 - 1, self.1),
-            Self(self.0 + 1, self.1),
-            Self(self.0, self.1 - 1),
-            Self(self.0, self.1 + 1),
-        ]
-    }
-}
-
-impl From<(usize, usize)> for Coord {
-    fn from(value: (usize, usize)) -> Self {
-        Self(value.0 as isize, value.1 as isize)
-    }
-}
-
 struct Map {
-    tiles: Array2<u8>,
+    tiles: Grid<u8>,
 }
 
 impl Map {
     fn tile(&self, coord: Coord) -> Option<u8> {
-        self.tiles.get(coord.as_sized()?).copied()
+        self.tiles.get(coord).copied()
     }
 
     fn trailhead_score(&self, coord: Coord) -> (usize, usize) {
-        let mut peaks = HashSet::new();
+        let mut peaks = BitGrid::new(self.tiles.width(), self.tiles.height());
         let mut summa = 0;
         let mut queue = VecDeque::new();
         queue.push_back(coord);
@@ -55,7 +31,7 @@ impl Map {
                 summa += 1;
                 continue;
             }
-            for neighbor in coord.neighbors() {
+            for neighbor in coord.neighbors4() {
                 let Some(next_tile) = self.tile(neighbor) else {
                     continue;
                 };
@@ -69,44 +45,34 @@ impl Map {
 
     fn score(&self) -> usize {
         self.tiles
-            .indexed_iter()
-            .filter_map(|(coord, &tile)| if tile == 0 { Some(coord.into()) } else { None })
+            .find_all(&0)
             .map(|coord| self.trailhead_score(coord).0)
             .sum()
     }
 
     fn distinct_score(&self) -> usize {
         self.tiles
-            .indexed_iter()
-            .filter_map(|(coord, &tile)| if tile == 0 { Some(coord.into()) } else { None })
+            .find_all(&0)
             .map(|coord| self.trailhead_score(coord).1)
             .sum()
     }
 }
 
-impl From<&str> for Map {
-    fn from(value: &str) -> Self {
-        let chars: Vec<_> = value
-            .lines()
-            .flat_map(|line| line.as_bytes())
-            .map(|&b| if b.is_ascii_digit() { b - b'0' } else { b'.' })
-            .collect();
-        let height = value.lines().count();
-        let width = chars.len() / height;
-        let shape = (height, width);
-        let tiles = Array2::from_shape_vec(shape, chars).unwrap();
-        Self { tiles }
+impl Map {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let tiles = Grid::try_parse(&Input::new(input), input, |c| {
+            c.to_digit(10).map_or(b'.', |d| d as u8)
+        })?;
+        Ok(Self { tiles })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    Some(map.score())
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(Map::parse(input)?.score())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    Some(map.distinct_score())
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(Map::parse(input)?.distinct_score())
 }
 
 #[cfg(test)]
@@ -116,12 +82,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(36));
+        assert_eq!(result, Ok(36));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(81));
+        assert_eq!(result, Ok(81));
     }
 }
@@ -1,12 +1,33 @@
-use regex::Regex;
+use advent_of_code::{
+    math::{egcd, solve_2x2, Solutions2},
+    parse::{Input, ParseError},
+};
 
 advent_of_code::solution!(13);
 
+/// The values of `k` for which `0 <= base + k * step <= max`, as optional lower and upper bounds.
+/// Returns `None` if there are none.
+fn k_range(base: i128, step: i128, max: Option<i128>) -> Option<(Option<i128>, Option<i128>)> {
+    let floor_div = |n: i128, d: i128| n.div_euclid(d);
+    let ceil_div = |n: i128, d: i128| -(-n).div_euclid(d);
+    match step.signum() {
+        0 => (base >= 0 && max.is_none_or(|max| base <= max)).then_some((None, None)),
+        1 => Some((
+            Some(ceil_div(-base, step)),
+            max.map(|max| floor_div(max - base, step)),
+        )),
+        _ => Some((
+            max.map(|max| ceil_div(base - max, -step)),
+            Some(floor_div(base, -step)),
+        )),
+    }
+}
+
 #[derive(Debug)]
 struct Game {
-    a_button: (isize, isize),
-    b_button: (isize, isize),
-    prize: (isize, isize),
+    a_button: (i64, i64),
+    b_button: (i64, i64),
+    prize: (i64, i64),
 }
 
 impl Game {
@@ -15,65 +36,107 @@ impl Game {
         self.prize.1 += 10000000000000;
     }
 
-    fn tokens(&self, max_play: Option<isize>) -> Option<usize> {
-        let det = self.a_button.0 * self.b_button.1 - self.a_button.1 * self.b_button.0;
-        let det_a = self.prize.0 * self.b_button.1 - self.prize.1 * self.b_button.0;
-        let det_b = self.a_button.0 * self.prize.1 - self.a_button.1 * self.prize.0;
+    fn tokens(&self, max_presses: Option<i64>) -> Option<usize> {
+        let ((ax, ay), (bx, by), (px, py)) = (self.a_button, self.b_button, self.prize);
+        let (a_presses, b_presses) = match solve_2x2([[ax, bx], [ay, by]], [px, py]) {
+            Solutions2::Unique(a, b) => (a, b),
+            Solutions2::None => return None,
+            Solutions2::Dependent => self.cheapest_collinear(max_presses)?,
+        };
 
-        if det_a % det != 0 || det_b % det != 0 {
+        let in_range = |presses: i64| presses >= 0 && max_presses.is_none_or(|max| presses <= max);
+        if !in_range(a_presses) || !in_range(b_presses) {
             return None;
         }
 
-        let a_presses = det_a / det;
-        let b_presses = det_b / det;
-        if let Some(max_presses) = max_play {
-            if a_presses > max_presses || b_presses > max_presses {
+        let tokens = a_presses * 3 + b_presses;
+        Some(tokens as usize)
+    }
+
+    /// Cheapest presses when both buttons move along the line towards the prize, so only one
+    /// equation `a * ca + b * cb = p` constrains them.
+    fn cheapest_collinear(&self, max_presses: Option<i64>) -> Option<(i64, i64)> {
+        let ((ax, ay), (bx, by), (px, py)) = (self.a_button, self.b_button, self.prize);
+        let (ca, cb, p) = if (ax, bx) != (0, 0) {
+            (ax, bx, px)
+        } else {
+            (ay, by, py)
+        };
+        if (ca, cb) == (0, 0) {
+            // neither button moves the claw, and the system is consistent: the prize is at 0.
+            return Some((0, 0));
+        }
+
+        // all solutions are `(a0 + k * da, b0 - k * db)` for integral `k`.
+        let (g, x, y) = egcd(ca, cb);
+        if p % g != 0 {
+            return None;
+        }
+        let (a0, b0) = (i128::from(x * (p / g)), i128::from(y * (p / g)));
+        let (da, db) = (i128::from(cb / g), i128::from(ca / g));
+
+        let max = max_presses.map(i128::from);
+        let (mut lo, mut hi) = (None, None);
+        for (base, step) in [(a0, da), (b0, -db)] {
+            let (k_lo, k_hi) = k_range(base, step, max)?;
+            lo = lo.max(k_lo);
+            hi = match (hi, k_hi) {
+                (Some(hi), Some(k_hi)) => Some(k_hi.min(hi)),
+                (hi, k_hi) => hi.or(k_hi),
+            };
+        }
+        if let (Some(lo), Some(hi)) = (lo, hi) {
+            if lo > hi {
                 return None;
             }
         }
 
-        let prize = a_presses * 3 + b_presses;
-        Some(prize as usize)
+        // tokens change by `3 * da - db` per step of `k`.
+        let k = match (3 * da - db).signum() {
+            1 => lo?,
+            -1 => hi?,
+            _ => lo.or(hi)?,
+        };
+        let a = i64::try_from(a0 + k * da).ok()?;
+        let b = i64::try_from(b0 - k * db).ok()?;
+        Some((a, b))
     }
-}
 
-impl From<&str> for Game {
-    fn from(value: &str) -> Self {
-        let re = Regex::new(
-            r"Button A: X\+(\d+), Y\+(\d+)\nButton B: X\+(\d+), Y\+(\d+)\nPrize: X=(\d+), Y=(\d+)",
-        )
-        .unwrap();
-        let m = re.captures(value).unwrap();
-        let a_button = (m[1].parse().unwrap(), m[2].parse().unwrap());
-        let b_button = (m[3].parse().unwrap(), m[4].parse().unwrap());
-        let prize = (m[5].parse().unwrap(), m[6].parse().unwrap());
-        Self {
-            a_button,
-            b_button,
-            prize,
-        }
+    fn parse(input: &Input, section: &str) -> Result<Self, ParseError> {
+        let [ax, ay, bx, by, px, py] = input.ints_n(section)?;
+        Ok(Self {
+            a_button: (ax, ay),
+            b_button: (bx, by),
+            prize: (px, py),
+        })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let ans = input
-        .split("\n\n")
-        .map(Game::from)
+fn games(input: &str) -> Result<Vec<Game>, ParseError> {
+    let input = Input::new(input);
+    input
+        .sections()
+        .map(|section| Game::parse(&input, section))
+        .collect()
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let ans = games(input)?
+        .into_iter()
         .filter_map(|game| game.tokens(Some(100)))
         .sum();
-    Some(ans)
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let ans = input
-        .split("\n\n")
-        .map(Game::from)
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let ans = games(input)?
+        .into_iter()
         .filter_map(|mut game| {
             game.correct_position();
             game.tokens(None)
         })
         .sum();
-    Some(ans)
+    Ok(ans)
 }
 
 #[cfg(test)]
@@ -83,12 +146,26 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(480));
+        assert_eq!(result, Ok(480));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(875318608908));
+        assert_eq!(result, Ok(875318608908));
+    }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let game = |prize| Game {
+            a_button: (2, 2),
+            b_button: (3, 3),
+            prize,
+        };
+        assert_eq!(game((7, 7)).tokens(None), Some(7));
+        assert_eq!(game((9, 9)).tokens(None), Some(3));
+        assert_eq!(game((7, 7)).tokens(Some(1)), None);
+        assert_eq!(game((1, 1)).tokens(None), None);
+        assert_eq!(game((7, 8)).tokens(None), None);
     }
 }
@@ -1,36 +1,35 @@
+use advent_of_code::{
+    geom::{Coord, Vec2},
+    grid::{BitGrid, Grid},
+    math,
+    parse::{Input, ParseError},
+    template::arg_value,
+};
 use ndarray::Array2;
-use std::{cmp::Ordering, collections::HashSet};
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
 
-advent_of_code::solution!(14);
+advent_of_code::solution!(14;
+    "--frames" => export_frames,
+    "--play" => play,
+    "--compare" => compare,
+);
 
 const WIDTH: isize = 101;
 const HEIGHT: isize = 103;
+/// Both sides are prime, so the positions repeat after `WIDTH * HEIGHT` ticks.
+const PERIOD: usize = (WIDTH * HEIGHT) as usize;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Vec2 {
-    x: isize,
-    y: isize,
-}
-
-impl std::ops::AddAssign for Vec2 {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-    }
-}
-
-impl From<&str> for Vec2 {
-    fn from(value: &str) -> Self {
-        let (x, y) = value.split_once(",").unwrap();
-        let x = x.parse().unwrap();
-        let y = y.parse().unwrap();
-        Self { x, y }
-    }
-}
-
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Robot {
-    pos: Vec2,
+    pos: Coord,
     vel: Vec2,
 }
 
@@ -41,6 +40,15 @@ impl Robot {
         self.pos.y = self.pos.y.rem_euclid(HEIGHT);
     }
 
+    /// The position after `ticks` ticks, without stepping through them.
+    fn position_at(&self, ticks: usize) -> Coord {
+        let ticks = (ticks % PERIOD) as isize;
+        Coord::new(
+            (self.pos.x + self.vel.x * ticks).rem_euclid(WIDTH),
+            (self.pos.y + self.vel.y * ticks).rem_euclid(HEIGHT),
+        )
+    }
+
     fn quadrant(&self) -> Option<usize> {
         let horizontal = self.pos.x.cmp(&(WIDTH / 2));
         let vertical = self.pos.y.cmp(&(HEIGHT / 2));
@@ -54,30 +62,24 @@ impl Robot {
     }
 }
 
-impl From<&str> for Robot {
-    fn from(value: &str) -> Self {
-        let (pos, vel) = value.split_once(" ").unwrap();
-        let pos = pos.strip_prefix("p=").unwrap().into();
-        let vel = vel.strip_prefix("v=").unwrap().into();
-        Self { pos, vel }
-    }
+fn robots(input: &str) -> Result<Vec<Robot>, ParseError> {
+    let input = Input::new(input);
+    input
+        .as_str()
+        .lines()
+        .map(|line| {
+            let [x, y, dx, dy] = input.ints_n(line)?;
+            Ok(Robot {
+                pos: Coord::new(x, y),
+                vel: Vec2::new(dx, dy),
+            })
+        })
+        .collect()
 }
 
-#[allow(dead_code)]
-fn print_map(map: &Array2<char>) {
-    let mut str = String::new();
-    for row in map.rows() {
-        for c in row {
-            str.push(*c);
-        }
-        str.push('\n');
-    }
-    println!("{}", str);
-}
-
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
     let mut quadrants = [0; 4];
-    for mut robot in input.lines().map(Robot::from) {
+    for mut robot in robots(input)? {
         for _ in 0..100 {
             robot.tick();
         }
@@ -85,27 +87,265 @@ pub fn part_one(input: &str) -> Option<usize> {
             quadrants[quad] += 1;
         }
     }
-    Some(quadrants.into_iter().product())
+    Ok(quadrants.into_iter().product())
+}
+
+/// The tick with the least spread along one axis of length `size`, where `coord` picks the axis.
+/// Positions along an axis repeat every `size` ticks, so only those ticks need checking.
+fn least_spread_tick(robots: &[Robot], size: isize, coord: impl Fn(Coord) -> isize) -> usize {
+    let n = robots.len() as i64;
+    (0..size as usize)
+        .min_by_key(|&ticks| {
+            let (sum, sum_sq) = robots.iter().fold((0, 0), |(sum, sum_sq), robot| {
+                let x = coord(robot.position_at(ticks)) as i64;
+                (sum + x, sum_sq + x * x)
+            });
+            // n² times the variance, which avoids dividing.
+            n * sum_sq - sum * sum
+        })
+        .unwrap()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut robots: Vec<_> = input.lines().map(Robot::from).collect();
+/// The tick where the robots cluster, found as the tick of least variance of x mod `WIDTH` and of
+/// y mod `HEIGHT`, joined with the chinese remainder theorem.
+fn clustered_tick(robots: &[Robot]) -> usize {
+    let x_ticks = least_spread_tick(robots, WIDTH, |pos| pos.x);
+    let y_ticks = least_spread_tick(robots, HEIGHT, |pos| pos.y);
+    let (ticks, _) = math::crt(&[
+        (x_ticks as i64, WIDTH as i64),
+        (y_ticks as i64, HEIGHT as i64),
+    ])
+    .expect("the sides are coprime");
+    ticks as usize
+}
 
-    for i in 1.. {
+/// The first tick where no two robots share a tile, the heuristic part two used before
+/// [`clustered_tick`].
+fn first_unique_tick(robots: &[Robot]) -> Option<usize> {
+    let mut robots = robots.to_vec();
+    let mut visited = BitGrid::new(WIDTH as usize, HEIGHT as usize);
+    for i in 1..=PERIOD {
         for robot in &mut robots {
             robot.tick();
         }
 
-        let mut visited = HashSet::new();
+        visited.clear();
         if robots.iter().all(|robot| visited.insert(robot.pos)) {
-            // let mut map = Array2::from_elem((HEIGHT as usize, WIDTH as usize), ' ');
-            // for robot in &robots {
-            //     let pos = (robot.pos.y as usize, robot.pos.x as usize);
-            //     map[pos] = 'X';
-            // }
-            // print_map(&map);
             return Some(i);
         }
     }
     None
 }
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(clustered_tick(&robots(input)?))
+}
+
+/* -------------------------------------------------------------------------- */
+
+type Encoder = fn(&Grid<u8>) -> Vec<u8>;
+
+/// Number of robots on every tile after `ticks` ticks.
+fn frame(robots: &[Robot], ticks: usize) -> Grid<u8> {
+    let mut frame: Grid<u8> = Grid::from_array(Array2::zeros((HEIGHT as usize, WIDTH as usize)));
+    for robot in robots {
+        let tile = &mut frame[robot.position_at(ticks)];
+        *tile = tile.saturating_add(1);
+    }
+    frame
+}
+
+/// Binary PBM, one bit per tile that is black if any robot stands on it.
+fn to_pbm(frame: &Grid<u8>) -> Vec<u8> {
+    let mut image = format!("P4\n{} {}\n", frame.width(), frame.height()).into_bytes();
+    for row in frame.rows() {
+        // rows are padded to whole bytes.
+        for chunk in row.as_slice().unwrap().chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (i, &count)| byte | u8::from(count > 0) << (7 - i));
+            image.push(byte);
+        }
+    }
+    image
+}
+
+/// Binary PPM on a black background, with brighter green for tiles with more robots.
+fn to_ppm(frame: &Grid<u8>) -> Vec<u8> {
+    let mut image = format!("P6\n{} {}\n255\n", frame.width(), frame.height()).into_bytes();
+    for &count in frame.iter() {
+        let green = match count {
+            0 => 0,
+            count => 95_u8.saturating_add(count.saturating_mul(80)),
+        };
+        image.extend([0, green, 0]);
+    }
+    image
+}
+
+/// The ticks selected by `--from` (0 by default), `--to` (one period later by default, inclusive)
+/// and `--every` (1 by default).
+fn selected_ticks() -> Result<impl Iterator<Item = usize>, String> {
+    let option = |flag: &str| {
+        arg_value(flag)
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .map_err(|err| format!("cannot parse {flag} `{value}`: {err}"))
+            })
+            .transpose()
+    };
+    let from = option("--from")?.unwrap_or(0);
+    let to = option("--to")?.unwrap_or(from + PERIOD - 1);
+    let every = option("--every")?.unwrap_or(1);
+    if every == 0 {
+        return Err("--every must be at least 1".to_string());
+    }
+    Ok((from..=to).step_by(every))
+}
+
+/// Writes one image per selected tick to the directory following `--frames`, as PBM or, with
+/// `--format ppm`, as PPM showing how many robots share a tile.
+fn export_frames(input: &str) {
+    if let Err(err) = try_export_frames(input) {
+        eprintln!("{err}");
+    }
+}
+
+fn try_export_frames(input: &str) -> Result<(), Box<dyn Error>> {
+    let dir = arg_value("--frames").ok_or("--frames needs an output directory")?;
+    let (extension, encode): (_, Encoder) = match arg_value("--format").as_deref() {
+        None | Some("pbm") => ("pbm", to_pbm),
+        Some("ppm") => ("ppm", to_ppm),
+        Some(format) => return Err(format!("unknown format `{format}`").into()),
+    };
+    let robots = robots(input)?;
+    let dir = Path::new(&dir);
+    fs::create_dir_all(dir)?;
+
+    let mut written = 0;
+    for ticks in selected_ticks()? {
+        let path = dir.join(format!("{ticks:05}.{extension}"));
+        fs::write(path, encode(&frame(&robots, ticks)))?;
+        written += 1;
+    }
+    println!("wrote {written} frames to {}", dir.display());
+    Ok(())
+}
+
+/// Two rows of tiles per line of text, using half blocks.
+fn render(frame: &Grid<u8>) -> String {
+    let mut text = String::with_capacity(frame.len() * 2);
+    for rows in frame.rows().into_iter().collect::<Vec<_>>().chunks(2) {
+        for x in 0..frame.width() {
+            let upper = rows[0][x] > 0;
+            let lower = rows.get(1).is_some_and(|row| row[x] > 0);
+            text.push(match (upper, lower) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Animates the selected ticks in the terminal at `--fps` frames per second, 10 by default.
+fn play(input: &str) {
+    if let Err(err) = try_play(input) {
+        eprintln!("{err}");
+    }
+}
+
+fn try_play(input: &str) -> Result<(), Box<dyn Error>> {
+    let fps = match arg_value("--fps") {
+        Some(fps) => fps.parse::<f64>()?,
+        None => 10.0,
+    };
+    if fps.is_nan() || fps <= 0.0 {
+        return Err("--fps must be positive".into());
+    }
+    let robots = robots(input)?;
+    let delay = Duration::from_secs_f64(1.0 / fps);
+
+    let mut stdout = io::stdout().lock();
+    // clear the screen once, then redraw over the previous frame.
+    write!(stdout, "\x1b[2J")?;
+    for ticks in selected_ticks()? {
+        let frame = render(&frame(&robots, ticks));
+        writeln!(stdout, "\x1b[H{frame}tick {ticks}\x1b[K")?;
+        stdout.flush()?;
+        thread::sleep(delay);
+    }
+    Ok(())
+}
+
+/// Runs both detectors of part two, to check the heuristic against the variance.
+fn compare(input: &str) {
+    let robots = match robots(input) {
+        Ok(robots) => robots,
+        Err(err) => return eprintln!("{err}"),
+    };
+
+    let timer = Instant::now();
+    let clustered = clustered_tick(&robots);
+    println!("least variance:   {clustered} ({:?})", timer.elapsed());
+
+    let timer = Instant::now();
+    match first_unique_tick(&robots) {
+        Some(unique) => println!("unique positions: {unique} ({:?})", timer.elapsed()),
+        None => println!("unique positions: never ({:?})", timer.elapsed()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_at() {
+        let start = robots("p=2,4 v=2,-3").unwrap().remove(0);
+        let mut robot = start.clone();
+        for ticks in 0..250 {
+            assert_eq!(start.position_at(ticks), robot.pos, "{ticks}");
+            robot.tick();
+        }
+    }
+
+    #[test]
+    fn test_clustered_tick() {
+        // a 6x6 block of robots at tick 5000 among as many scattered ones.
+        const TICKS: isize = 5000;
+        let mut state = 0x9e37_79b9_u32;
+        let mut random = |max: isize| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as isize % max
+        };
+        let mut robots = Vec::new();
+        for i in 0..72 {
+            let vel = Vec2::new(random(WIDTH) - WIDTH / 2, random(HEIGHT) - HEIGHT / 2);
+            let pos = if i < 36 {
+                Coord::new(40 + i % 6, 60 + i / 6) - vel * TICKS
+            } else {
+                Coord::new(random(WIDTH), random(HEIGHT))
+            };
+            robots.push(Robot {
+                pos: Coord::new(pos.x.rem_euclid(WIDTH), pos.y.rem_euclid(HEIGHT)),
+                vel,
+            });
+        }
+        assert_eq!(clustered_tick(&robots), TICKS as usize);
+    }
+
+    #[test]
+    fn test_pbm() {
+        let frame = Grid::from_array(Array2::from_shape_fn((2, 10), |(y, x)| u8::from(x == y)));
+        let image = to_pbm(&frame);
+        assert_eq!(image, b"P4\n10 2\n\x80\x00\x40\x00");
+    }
+}
@@ -1,181 +1,164 @@
 use std::collections::{HashSet, VecDeque};
 
+use advent_of_code::{
+    geom::{Coord, Dir4, Vec2},
+    grid::Grid,
+    parse::{Input, ParseError},
+    template::{arg_value, ANSI_BOLD, ANSI_RESET},
+};
 use ndarray::Array2;
 
-advent_of_code::solution!(15);
+advent_of_code::solution!(15; "--replay" => replay);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct Coord(isize, isize);
-
-impl Coord {
-    fn indexable(&self) -> (usize, usize) {
-        assert!(self.0 >= 0);
-        assert!(self.1 >= 0);
-        (self.0 as usize, self.1 as usize)
-    }
-}
-
-impl std::ops::AddAssign for Coord {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
-    }
-}
-
-impl std::ops::Add for Coord {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl std::ops::Sub for Coord {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self(self.0 - rhs.0, self.1 - rhs.1)
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn vector(&self) -> Coord {
-        match *self {
-            Direction::Up => Coord(-1, 0),
-            Direction::Down => Coord(1, 0),
-            Direction::Left => Coord(0, -1),
-            Direction::Right => Coord(0, 1),
-        }
-    }
-}
-
-impl From<char> for Direction {
-    fn from(value: char) -> Self {
-        match value {
-            '^' => Direction::Up,
-            '<' => Direction::Left,
-            'v' => Direction::Down,
-            '>' => Direction::Right,
-            c => panic!("Unexpected Direction tile: {}", c),
-        }
-    }
-}
+const ROBOT_COLOR: &str = "\x1b[33m";
+const PUSHED_COLOR: &str = "\x1b[32m";
 
 struct Map {
-    tiles: Array2<char>,
+    tiles: Grid<char>,
     robot: Coord,
+    /// Width of every box: `O` if 1, otherwise `[`, `box_width - 2` times `=` and `]`.
+    box_width: usize,
 }
 
 impl Map {
-    fn new(tiles: Array2<char>) -> Self {
+    /// Parses the map in `text`, where boxes are `O` or, once widened, `[=]` with any number of
+    /// `=` in between.
+    fn parse(input: &Input, text: &str) -> Result<Self, ParseError> {
+        let tiles = Grid::try_parse(input, text, |c| c)?;
         let robot = tiles
-            .indexed_iter()
-            .find_map(|(coord, ch)| if *ch == '@' { Some(coord) } else { None })
-            .map(|(y, x)| Coord(y as isize, x as isize))
-            .unwrap();
-        Self { tiles, robot }
+            .find(&'@')
+            .ok_or_else(|| input.error(text, "expected a robot `@` on the map"))?;
+        let box_width = match text.find('[') {
+            Some(left) => {
+                let line = text[left..].lines().next().unwrap_or_default();
+                line.find(']')
+                    .ok_or_else(|| input.error(line, "expected a `]` closing the box"))?
+                    + 1
+            }
+            None => 1,
+        };
+        Ok(Self {
+            tiles,
+            robot,
+            box_width,
+        })
+    }
+
+    /// The tile at `offset` of a box `width` tiles wide.
+    fn box_tile(width: usize, offset: usize) -> char {
+        match offset {
+            _ if width == 1 => 'O',
+            0 => '[',
+            _ if offset == width - 1 => ']',
+            _ => '=',
+        }
     }
 
-    fn expand(&self) -> Self {
+    /// Widens every tile `factor` times. Boxes become `factor` tiles wide, the robot stays on the
+    /// leftmost tile.
+    fn expand(&self, factor: usize) -> Self {
+        assert!(factor > 0, "cannot expand by 0");
         let (height, width) = self.tiles.dim();
-        let mut tiles = Array2::from_elem((height, width * 2), '.');
+        let mut tiles = Grid::from_array(Array2::from_elem((height, width * factor), '.'));
         for ((y, x), ch) in self.tiles.indexed_iter() {
-            match ch {
-                '#' => {
-                    tiles[(y, 2 * x)] = '#';
-                    tiles[(y, 2 * x + 1)] = '#';
-                }
-                'O' => {
-                    tiles[(y, 2 * x)] = '[';
-                    tiles[(y, 2 * x + 1)] = ']';
-                }
-                '@' => {
-                    tiles[(y, 2 * x)] = '@';
-                }
-                '.' => {}
-                _ => unreachable!(""),
+            for i in 0..factor {
+                tiles[(y, factor * x + i)] = match ch {
+                    '#' => '#',
+                    'O' => Self::box_tile(factor, i),
+                    '@' if i == 0 => '@',
+                    '@' | '.' => '.',
+                    ch => unreachable!("Unexpected tile: {}", ch),
+                };
             }
         }
         let mut robot = self.robot;
-        robot.1 *= 2;
-        Self { tiles, robot }
-    }
-
-    fn move_small(&mut self, dir: Direction) {
-        let mv = dir.vector();
-        let mut pos = self.robot;
-        loop {
-            match self.tiles[pos.indexable()] {
-                '#' => return,
-                '.' => break,
-                'O' | '@' => pos += mv,
-                ch => unreachable!("Unexpected tile: {}", ch),
-            }
+        robot.x *= factor as isize;
+        Self {
+            tiles,
+            robot,
+            box_width: factor,
         }
+    }
 
-        while pos != self.robot {
-            let prev = pos - mv;
-            self.tiles[pos.indexable()] = self.tiles[prev.indexable()];
-            self.tiles[prev.indexable()] = '.';
-            pos = prev;
+    /// The leftmost tile of the box covering `pos`.
+    fn box_start(&self, mut pos: Coord) -> Coord {
+        while matches!(self.tiles[pos], '=' | ']') {
+            pos.x -= 1;
         }
-        self.robot += mv;
+        pos
     }
 
-    fn move_large(&mut self, dir: Direction) {
+    /// Moves the robot, pushing every box in the way unless one of them hits a wall. Returns the
+    /// leftmost tiles of the pushed boxes at their new positions.
+    fn move_robot(&mut self, dir: Dir4) -> Vec<Coord> {
+        if self.box_width == 1 || !dir.is_vertical() {
+            return self.move_in_line(dir);
+        }
         let mv = dir.vector();
-        let left = Direction::Left.vector();
-        let right = Direction::Right.vector();
 
-        let mut region = HashSet::new();
-        let mut queue = VecDeque::new();
-        queue.push_back(self.robot);
+        // pushing up or down, a box pushes every box overlapping the tiles it moves into.
+        let mut boxes = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([self.robot + mv]);
         while let Some(pos) = queue.pop_front() {
-            match self.tiles[pos.indexable()] {
-                '#' => return,
+            match self.tiles[pos] {
+                '#' => return Vec::new(),
                 '.' => continue,
-                '@' => {
-                    queue.push_back(pos + mv);
-                    region.insert((pos, '@'));
-                }
-                ']' => {
-                    if dir == Direction::Up || dir == Direction::Down {
-                        queue.push_back(pos + mv);
-                        queue.push_back(pos + mv + left);
-                    } else {
-                        queue.push_back(pos + left + left);
+                'O' | '[' | '=' | ']' => {
+                    let start = self.box_start(pos);
+                    if !seen.insert(start) {
+                        continue;
                     }
-                    region.insert((pos + left, '['));
-                    region.insert((pos, ']'));
-                }
-                '[' => {
-                    if dir == Direction::Up || dir == Direction::Down {
-                        queue.push_back(pos + mv);
-                        queue.push_back(pos + mv + right);
-                    } else {
-                        queue.push_back(pos + right + right);
-                    }
-                    region.insert((pos, '['));
-                    region.insert((pos + right, ']'));
+                    boxes.push(start);
+                    queue.extend((0..self.box_width).map(|i| pos_at(start, i) + mv));
                 }
                 ch => unreachable!("Unexpected tile: {}", ch),
             }
         }
 
-        for (coord, _) in &region {
-            self.tiles[coord.indexable()] = '.';
+        for &start in &boxes {
+            for i in 0..self.box_width {
+                self.tiles[pos_at(start, i)] = '.';
+            }
+        }
+        for start in &mut boxes {
+            *start += mv;
+            for i in 0..self.box_width {
+                self.tiles[pos_at(*start, i)] = Self::box_tile(self.box_width, i);
+            }
         }
-        for (coord, ch) in region {
-            self.tiles[(coord + mv).indexable()] = ch;
+        self.tiles[self.robot] = '.';
+        self.robot += mv;
+        self.tiles[self.robot] = '@';
+        boxes
+    }
+
+    /// [`Map::move_robot`] where the pushed boxes form a single row or column, which saves
+    /// tracking them one by one.
+    fn move_in_line(&mut self, dir: Dir4) -> Vec<Coord> {
+        let mv = dir.vector();
+        let mut pos = self.robot + mv;
+        loop {
+            match self.tiles[pos] {
+                '#' => return Vec::new(),
+                '.' => break,
+                'O' | '[' | '=' | ']' => pos += mv,
+                ch => unreachable!("Unexpected tile: {}", ch),
+            }
         }
 
+        let mut boxes = Vec::new();
+        while pos != self.robot {
+            let prev = pos - mv;
+            self.tiles[pos] = self.tiles[prev];
+            self.tiles[prev] = '.';
+            if matches!(self.tiles[pos], 'O' | '[') {
+                boxes.push(pos);
+            }
+            pos = prev;
+        }
         self.robot += mv;
+        boxes
     }
 
     fn score(&self) -> usize {
@@ -191,52 +174,113 @@ impl Map {
             .map(|(y, x)| 100 * y + x)
             .sum()
     }
-}
 
-impl From<&str> for Map {
-    fn from(value: &str) -> Self {
-        let chars: Vec<_> = value.lines().flat_map(|line| line.chars()).collect();
-        let height = value.lines().count();
-        let width = chars.len() / height;
-        let shape = (height, width);
-        let tiles = Array2::from_shape_vec(shape, chars).unwrap();
-        Map::new(tiles)
+    /// The warehouse with the robot and the boxes starting at `pushed` highlighted.
+    fn render(&self, pushed: &[Coord]) -> String {
+        let mut highlighted = HashSet::new();
+        for &start in pushed {
+            highlighted.extend((0..self.box_width).map(|i| pos_at(start, i)));
+        }
+        let mut text = String::new();
+        for (y, row) in self.tiles.rows().into_iter().enumerate() {
+            for (x, &ch) in row.iter().enumerate() {
+                let pos = Coord::from_index((y, x));
+                if pos == self.robot {
+                    text.push_str(&format!("{ANSI_BOLD}{ROBOT_COLOR}{ch}{ANSI_RESET}"));
+                } else if highlighted.contains(&pos) {
+                    text.push_str(&format!("{PUSHED_COLOR}{ch}{ANSI_RESET}"));
+                } else {
+                    text.push(ch);
+                }
+            }
+            text.push('\n');
+        }
+        text
     }
 }
 
-struct Input {
+/// The tile `offset` tiles east of `start`.
+fn pos_at(start: Coord, offset: usize) -> Coord {
+    start + Vec2::new(offset as isize, 0)
+}
+
+struct Warehouse {
     map: Map,
-    dirs: Vec<Direction>,
+    dirs: Vec<Dir4>,
 }
 
-impl From<&str> for Input {
-    fn from(value: &str) -> Self {
-        let (map_str, dirs_str) = value.split_once("\n\n").unwrap();
-        let map = map_str.into();
+impl Warehouse {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let mut sections = input.sections();
+        let (map_str, dirs_str) = match (sections.next(), sections.next()) {
+            (Some(map), Some(dirs)) => (map, dirs),
+            _ => return Err(input.error(input.as_str(), "expected a map and moves")),
+        };
+        let map = Map::parse(&input, map_str)?;
         let dirs = dirs_str
             .lines()
-            .flat_map(|line| line.chars())
-            .map(Direction::from)
-            .collect();
-        Self { map, dirs }
+            .flat_map(|line| line.char_indices().map(move |(i, c)| (&line[i..], c)))
+            .map(|(at, c)| Dir4::try_from(c).map_err(|err| input.error(at, err)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { map, dirs })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let Input { mut map, dirs } = input.into();
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let Warehouse { mut map, dirs } = Warehouse::parse(input)?;
     for dir in dirs {
-        map.move_small(dir);
+        map.move_robot(dir);
     }
-    Some(map.score())
+    Ok(map.score())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let Input { map, dirs } = input.into();
-    let mut map = map.expand();
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let Warehouse { map, dirs } = Warehouse::parse(input)?;
+    let mut map = map.expand(2);
     for dir in dirs {
-        map.move_large(dir);
+        map.move_robot(dir);
     }
-    Some(map.score())
+    Ok(map.score())
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Prints the warehouse after every move, or after every `--every` moves, with boxes widened
+/// `--width` times (1 by default).
+fn replay(input: &str) {
+    let option = |flag: &str| match arg_value(flag) {
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|&value| value > 0)
+            .ok_or(format!("{flag} must be a positive number, got `{value}`")),
+        None => Ok(1),
+    };
+    let (width, every) = match (option("--width"), option("--every")) {
+        (Ok(width), Ok(every)) => (width, every),
+        (Err(err), _) | (_, Err(err)) => return eprintln!("{err}"),
+    };
+
+    let Warehouse { map, dirs } = match Warehouse::parse(input) {
+        Ok(warehouse) => warehouse,
+        Err(err) => return eprintln!("{err}"),
+    };
+    let mut map = map.expand(width);
+    println!("initial state:\n{}", map.render(&[]));
+    for (i, &dir) in dirs.iter().enumerate() {
+        let pushed = map.move_robot(dir);
+        let moves = i + 1;
+        if moves % every == 0 || moves == dirs.len() {
+            let arrow = ['^', '>', 'v', '<'][dir as usize];
+            println!(
+                "move {moves}/{}: {arrow}\n{}",
+                dirs.len(),
+                map.render(&pushed)
+            );
+        }
+    }
+    println!("score: {}", map.score());
 }
 
 #[cfg(test)]
@@ -248,7 +292,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(2028));
+        assert_eq!(result, Ok(2028));
     }
 
     #[test]
@@ -256,7 +300,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(10092));
+        assert_eq!(result, Ok(10092));
     }
 
     #[test]
@@ -264,6 +308,62 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(9021));
+        assert_eq!(result, Ok(9021));
+    }
+
+    fn moved(map: &str, dirs: &str) -> String {
+        let mut map = Map::parse(&Input::new(map), map).unwrap();
+        for c in dirs.chars() {
+            map.move_robot(Dir4::try_from(c).unwrap());
+        }
+        map.tiles.to_string()
+    }
+
+    #[test]
+    fn test_push_wide_boxes() {
+        let map = "\
+###############
+#.............#
+#...[=].[=]...#
+#.....[=].....#
+#......@......#
+###############";
+        let pushed = "\
+###############
+#...[=].[=]...#
+#.....[=].....#
+#......@......#
+#.............#
+###############";
+        assert_eq!(moved(map, "^"), pushed);
+        assert_eq!(moved(map, "^^"), pushed);
+
+        assert_eq!(moved("#..[=][=]@#", "<"), "#.[=][=]@.#");
+        assert_eq!(moved("#..[=][=]@#", "<<<"), "#[=][=]@..#");
+        assert_eq!(moved("#@[==][==].#", ">>"), "#.@[==][==]#");
+    }
+
+    #[test]
+    fn test_boxes_stay_whole() {
+        let Warehouse { map, dirs } = Warehouse::parse(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ))
+        .unwrap();
+        let boxes = map.tiles.iter().filter(|&&ch| ch == 'O').count();
+        for width in 1..=5 {
+            let mut map = map.expand(width);
+            for &dir in &dirs {
+                map.move_robot(dir);
+            }
+            let text = map.tiles.to_string();
+            let whole: String = (0..width).map(|i| Map::box_tile(width, i)).collect();
+            assert_eq!(text.matches(&whole).count(), boxes, "{width}");
+            assert_eq!(
+                text.chars().filter(|ch| "O[=]".contains(*ch)).count(),
+                boxes * width,
+                "{width}"
+            );
+            assert_eq!(map.tiles[map.robot], '@');
+        }
     }
 }
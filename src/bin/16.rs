@@ -1,229 +1,210 @@
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use advent_of_code::{
+    geom::{Coord, Dir4},
+    grid::{BitGrid, Grid, GridMap},
+    parse::{Input, ParseError},
+    search::{self, Entry, Ledger, Search},
+    template::arg_value,
+};
 
-use ndarray::Array2;
+advent_of_code::solution!(16; "--render" => render);
 
-advent_of_code::solution!(16);
+type State = (Coord, Dir4);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Dir {
-    North,
-    East,
-    South,
-    West,
+/// What moving costs: a step forward, and a turn by 90 degrees on the spot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Costs {
+    step: usize,
+    turn: usize,
 }
 
-impl Dir {
-    fn clockwise(&self) -> Self {
-        match *self {
-            Dir::North => Dir::East,
-            Dir::East => Dir::South,
-            Dir::South => Dir::West,
-            Dir::West => Dir::North,
-        }
-    }
-
-    fn counterclockwise(&self) -> Self {
-        match *self {
-            Dir::North => Dir::West,
-            Dir::West => Dir::South,
-            Dir::South => Dir::East,
-            Dir::East => Dir::North,
-        }
-    }
-
-    fn walk(&self, coord: (usize, usize)) -> (usize, usize) {
-        let (y, x) = coord;
-        match *self {
-            Dir::North => (y - 1, x),
-            Dir::South => (y + 1, x),
-            Dir::East => (y, x + 1),
-            Dir::West => (y, x - 1),
+impl Default for Costs {
+    fn default() -> Self {
+        Self {
+            step: 1,
+            turn: 1000,
         }
     }
 }
 
-#[derive(Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    pos: (usize, usize),
-    dir: Dir,
-    path: Vec<(usize, usize)>,
+struct Map {
+    tiles: Grid<char>,
+    costs: Costs,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| other.path.len().cmp(&self.path.len()))
+impl Map {
+    fn start(&self) -> State {
+        let (height, _) = self.tiles.dim();
+        (Coord::from_index((height - 2, 1)), Dir4::East)
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    fn stop(&self) -> Coord {
+        let (_, width) = self.tiles.dim();
+        Coord::from_index((1, width - 2))
     }
-}
 
-struct Map {
-    tiles: Array2<char>,
-}
-
-impl Map {
-    fn score(&self) -> Option<usize> {
-        let (height, width) = self.tiles.dim();
-        let start = (height - 2, 1);
-        let stop = (1, width - 2);
+    /// An empty ledger for a search over the maze, recording `E` for each state.
+    fn ledger<E>(&self) -> GridMap<E, State> {
+        GridMap::new(self.tiles.width(), self.tiles.height())
+    }
 
-        let mut dist = HashMap::new();
-        let mut queue = BinaryHeap::new();
-        queue.push(State {
-            cost: 0,
-            pos: start,
-            dir: Dir::East,
-            path: vec![],
-        });
+    fn moves(&self, &(pos, dir): &State) -> impl Iterator<Item = (State, usize)> {
+        let ahead = pos.step(dir);
+        let forward = self
+            .tiles
+            .get(ahead)
+            .is_some_and(|&tile| tile != '#')
+            .then_some(((ahead, dir), self.costs.step));
+        forward.into_iter().chain([
+            ((pos, dir.turn_right()), self.costs.turn),
+            ((pos, dir.turn_left()), self.costs.turn),
+        ])
+    }
 
-        while let Some(State {
-            cost,
-            pos,
-            dir,
-            path: _,
-        }) = queue.pop()
-        {
-            if pos == stop {
-                return Some(cost);
-            }
+    /// The reindeer may reach the end facing any direction; returns the cheapest ones.
+    fn best_ends<L>(&self, search: &Search<State, L>) -> Vec<(State, usize)>
+    where
+        L: Ledger<State>,
+        L::Entry: Entry<State>,
+    {
+        let stop = self.stop();
+        let ends: Vec<_> = Dir4::ALL
+            .into_iter()
+            .filter_map(|dir| Some(((stop, dir), search.distance(&(stop, dir))?)))
+            .collect();
+        let lowest = ends.iter().map(|&(_, cost)| cost).min();
+        ends.into_iter()
+            .filter(|&(_, cost)| Some(cost) == lowest)
+            .collect()
+    }
 
-            if cost > *dist.get(&(pos, dir)).unwrap_or(&usize::MAX) {
-                continue;
-            }
+    fn score(&self) -> Option<usize> {
+        let stop = self.stop();
+        let search = search::dijkstra_in(
+            self.ledger(),
+            [self.start()],
+            |state| self.moves(state),
+            |&(pos, _)| pos == stop,
+        );
+        self.best_ends(&search).first().map(|&(_, cost)| cost)
+    }
 
-            let ahead = dir.walk(pos);
-            let candidates = [
-                State {
-                    cost: cost + 1,
-                    pos: ahead,
-                    dir,
-                    path: vec![],
-                },
-                State {
-                    cost: cost + 1000,
-                    pos,
-                    dir: dir.clockwise(),
-                    path: vec![],
-                },
-                State {
-                    cost: cost + 1000,
-                    pos,
-                    dir: dir.counterclockwise(),
-                    path: vec![],
-                },
-            ];
-            for next in candidates {
-                if self.tiles[next.pos] == '#' {
-                    continue;
-                }
-                if next.cost < *dist.get(&(next.pos, next.dir)).unwrap_or(&usize::MAX) {
-                    dist.insert((next.pos, next.dir), next.cost);
-                    queue.push(next);
-                }
-            }
+    /// Every tile on any of the cheapest paths, reconstructed from the predecessors of the
+    /// states on them. `None` if the end cannot be reached.
+    fn best_path_tiles(&self) -> Option<BitGrid> {
+        let stop = self.stop();
+        let search = search::dijkstra_with_predecessors_in(
+            self.ledger(),
+            [self.start()],
+            |state| self.moves(state),
+            |&(pos, _)| pos == stop,
+        );
+        let ends: Vec<_> = self
+            .best_ends(&search)
+            .into_iter()
+            .map(|(end, _)| end)
+            .collect();
+        if ends.is_empty() {
+            return None;
         }
-
-        None
+        let mut tiles = BitGrid::new(self.tiles.width(), self.tiles.height());
+        tiles.extend(
+            search
+                .on_shortest_paths(&ends)
+                .into_iter()
+                .map(|(pos, _)| pos),
+        );
+        Some(tiles)
     }
 
-    fn best_paths(&self) -> usize {
-        let (height, width) = self.tiles.dim();
-        let start = (height - 2, 1);
-        let stop = (1, width - 2);
-
-        let mut lowest_score = None;
-        let mut tiles_on_path = HashSet::new();
-        let mut ledger = HashMap::new();
-
-        let mut queue = BinaryHeap::new();
-        queue.push(State {
-            cost: 0,
-            pos: start,
-            dir: Dir::East,
-            path: vec![start],
-        });
-        while let Some(State {
-            cost,
-            pos,
-            dir,
-            path,
-        }) = queue.pop()
-        {
-            if pos == stop {
-                if cost == lowest_score.unwrap_or(cost) {
-                    lowest_score = Some(cost);
-                    tiles_on_path.extend(path.iter().copied());
-                }
-                continue;
-            }
-
-            let stored_cost = *ledger.get(&(pos, dir)).unwrap_or(&usize::MAX);
-            if cost > stored_cost {
-                continue;
-            }
-
-            for (rotation, rotation_cost) in [
-                (dir, 0),
-                (dir.clockwise(), 1000),
-                (dir.counterclockwise(), 1000),
-                (dir.clockwise().clockwise(), 2000),
-            ] {
-                let stored_cost = *ledger.get(&(pos, rotation)).unwrap_or(&usize::MAX);
-                let new_cost = cost + rotation_cost;
-                if new_cost < stored_cost {
-                    ledger.insert((pos, rotation), new_cost);
-                }
+    fn best_paths(&self) -> Option<usize> {
+        self.best_path_tiles().map(|tiles| tiles.len())
+    }
 
-                let ahead = rotation.walk(pos);
-                if self.tiles[ahead] != '#' {
-                    let stored_cost = *ledger.get(&(ahead, rotation)).unwrap_or(&usize::MAX);
-                    let new_cost = cost + rotation_cost + 1;
-                    if new_cost <= stored_cost {
-                        let mut new_path = path.clone();
-                        new_path.push(ahead);
-                        queue.push(State {
-                            cost: new_cost,
-                            pos: ahead,
-                            dir: rotation,
-                            path: new_path,
-                        });
-                        ledger.insert((ahead, rotation), new_cost);
-                    }
-                }
-            }
+    /// The maze with the given tiles, those of the cheapest paths, marked `O`.
+    fn overlay(&self, tiles: &BitGrid) -> Grid<char> {
+        let mut overlay = self.tiles.clone();
+        for pos in tiles.iter() {
+            overlay[pos] = 'O';
         }
-
-        tiles_on_path.len()
+        overlay
     }
 }
 
-impl From<&str> for Map {
-    fn from(value: &str) -> Self {
-        let chars: Vec<_> = value.lines().flat_map(|line| line.chars()).collect();
-        let height = value.lines().count();
-        let width = chars.len() / height;
-        let shape = (height, width);
-        let tiles = Array2::from_shape_vec(shape, chars).unwrap();
-        Self { tiles }
+impl Map {
+    /// Parses the maze, which has its start `S` in the bottom left and its end `E` in the top
+    /// right corner inside the walls.
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        let tiles = Grid::try_parse(input, input.as_str(), |c| c)?;
+        let (height, width) = tiles.dim();
+        if height < 3 || width < 3 {
+            return Err(input.error(
+                input.as_str(),
+                "expected a walled maze of at least 3x3 tiles",
+            ));
+        }
+        let map = Self {
+            tiles,
+            costs: Costs::default(),
+        };
+        if map.tiles[map.start().0] != 'S' {
+            return Err(input.error(
+                input.as_str(),
+                "expected the start `S` in the bottom left corner",
+            ));
+        }
+        if map.tiles[map.stop()] != 'E' {
+            return Err(input.error(
+                input.as_str(),
+                "expected the end `E` in the top right corner",
+            ));
+        }
+        Ok(map)
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    Some(map.score().unwrap())
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let input = Input::new(input);
+    let map = Map::parse(&input)?;
+    map.score()
+        .ok_or_else(|| input.error(input.as_str(), "the end cannot be reached"))
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let map: Map = input.into();
-    Some(map.best_paths())
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let input = Input::new(input);
+    let map = Map::parse(&input)?;
+    map.best_paths()
+        .ok_or_else(|| input.error(input.as_str(), "the end cannot be reached"))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Prints the maze with every tile on a cheapest path marked, using the costs given by `--step`
+/// and `--turn` instead of the puzzle's.
+fn render(input: &str) {
+    let mut costs = Costs::default();
+    for (flag, cost) in [("--step", &mut costs.step), ("--turn", &mut costs.turn)] {
+        if let Some(value) = arg_value(flag) {
+            match value.parse() {
+                Ok(value) => *cost = value,
+                Err(err) => return eprintln!("cannot parse {flag} `{value}`: {err}"),
+            }
+        }
+    }
+
+    let map = match Map::parse(&Input::new(input)) {
+        Ok(map) => Map { costs, ..map },
+        Err(err) => return eprintln!("{err}"),
+    };
+    let (Some(score), Some(tiles)) = (map.score(), map.best_path_tiles()) else {
+        return println!("the end cannot be reached");
+    };
+    println!("{}", map.overlay(&tiles));
+    println!(
+        "step {}, turn {}: lowest score {score}, {} tiles on the best paths",
+        costs.step,
+        costs.turn,
+        tiles.len()
+    );
 }
 
 #[cfg(test)]
@@ -235,7 +216,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(7036));
+        assert_eq!(result, Ok(7036));
     }
 
     #[test]
@@ -243,7 +224,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(11048));
+        assert_eq!(result, Ok(11048));
     }
 
     #[test]
@@ -251,7 +232,7 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(45));
+        assert_eq!(result, Ok(45));
     }
 
     #[test]
@@ -259,6 +240,53 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(64));
+        assert_eq!(result, Ok(64));
+    }
+
+    #[test]
+    fn test_unreachable_end() {
+        let input = "#####\n#..E#\n#####\n#S..#\n#####\n";
+        assert!(part_one(input).is_err());
+        assert!(part_two(input).is_err());
+    }
+
+    #[test]
+    fn test_overlay() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let map = Map::parse(&Input::new(&input)).unwrap();
+        let overlay = map.overlay(&map.best_path_tiles().unwrap());
+        assert_eq!(overlay.iter().filter(|&&tile| tile == 'O').count(), 45);
+        assert_eq!(overlay.find(&'S'), None);
+        assert_eq!(overlay.find(&'E'), None);
+        assert!(map
+            .tiles
+            .coords()
+            .all(|pos| (map.tiles[pos] == '#') == (overlay[pos] == '#')));
+    }
+
+    #[test]
+    fn test_costs() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let map = |step, turn| Map {
+            costs: Costs { step, turn },
+            ..Map::parse(&Input::new(&input)).unwrap()
+        };
+
+        // without turn costs, the reindeer takes any shortest path through the maze.
+        let plain = map(1, 0);
+        let stop = plain.stop();
+        let steps = search::bfs(
+            [plain.start().0],
+            |pos| {
+                pos.neighbors4()
+                    .filter(|&next| plain.tiles[next] != '#')
+                    .collect::<Vec<_>>()
+            },
+            |&pos| pos == stop,
+        )
+        .distance(&stop);
+        assert_eq!(plain.score(), steps);
+        assert_eq!(map(2, 0).score(), steps.map(|steps| 2 * steps));
+        assert_eq!(map(1, 1000).score(), Some(7036));
     }
 }
@@ -1,7 +1,25 @@
-use std::cmp::Ordering;
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    io::{self, Write},
+    ops::ControlFlow,
+    time::Instant,
+};
 
-advent_of_code::solution!(17);
+use advent_of_code::{
+    parse::{self, ParseError},
+    template::runner::run_part,
+};
 
+advent_of_code::solution!(17;
+    "--disassemble" => print_disassembly,
+    "--trace" => trace,
+    "--debug" => debug,
+    "--compare" => compare,
+    "--interpreter" => interpret,
+);
+
+#[derive(Clone, Copy, Debug)]
 enum Instructions {
     Adv,
     Bxl,
@@ -27,8 +45,64 @@ impl Instructions {
             _ => None,
         }
     }
+
+    fn mnemonic(&self) -> &'static str {
+        use Instructions::*;
+        match self {
+            Adv => "adv",
+            Bxl => "bxl",
+            Bst => "bst",
+            Jnz => "jnz",
+            Bxc => "bxc",
+            Out => "out",
+            Bdv => "bdv",
+            Cdv => "cdv",
+        }
+    }
+
+    /// What the instruction does with `operand`, e.g. `A = A / 2^B` for `adv 5`.
+    fn describe(&self, operand: usize) -> String {
+        let combo = match operand {
+            0..=3 => operand.to_string(),
+            4 => "A".to_string(),
+            5 => "B".to_string(),
+            6 => "C".to_string(),
+            _ => "<invalid>".to_string(),
+        };
+        use Instructions::*;
+        match self {
+            Adv => format!("A = A / 2^{combo}"),
+            Bxl => format!("B = B ^ {operand}"),
+            Bst => format!("B = {combo} % 8"),
+            Jnz => format!("jump to {operand} if A != 0"),
+            Bxc => "B = B ^ C".to_string(),
+            Out => format!("print {combo} % 8"),
+            Bdv => format!("B = A / 2^{combo}"),
+            Cdv => format!("C = A / 2^{combo}"),
+        }
+    }
 }
 
+/// The instruction at `ip`, e.g. `  0: adv 5  A = A / 2^B`.
+fn disassemble(program: &[usize], ip: usize) -> String {
+    let opcode = program[ip];
+    let operand = program.get(ip + 1).copied().unwrap_or_default();
+    match Instructions::decode(opcode) {
+        Some(instruction) => format!(
+            "{ip:>3}: {} {operand}  {}",
+            instruction.mnemonic(),
+            instruction.describe(operand)
+        ),
+        None => format!("{ip:>3}: invalid opcode {opcode}"),
+    }
+}
+
+fn format_output(output: &[usize]) -> String {
+    let output: Vec<_> = output.iter().map(|n| n.to_string()).collect();
+    output.join(",")
+}
+
+#[derive(Clone, Default)]
 struct Cpu {
     areg: usize,
     breg: usize,
@@ -44,86 +118,302 @@ impl Cpu {
         self.ip = 0;
     }
 
-    fn combo(&self, operand: usize) -> usize {
+    fn combo(&self, operand: usize) -> Result<usize, String> {
         match operand {
-            0..=3 => operand,
-            4 => self.areg,
-            5 => self.breg,
-            6 => self.creg,
-            _ => unreachable!("Invalid combo operand: {}", operand),
+            0..=3 => Ok(operand),
+            4 => Ok(self.areg),
+            5 => Ok(self.breg),
+            6 => Ok(self.creg),
+            _ => Err(format!(
+                "instruction at {} uses the reserved combo operand {operand}",
+                self.ip
+            )),
         }
     }
 
-    fn execute(&mut self, program: &[usize]) -> Vec<usize> {
-        let mut output = Vec::new();
-        while let Some(opcode) = program.get(self.ip) {
-            let operand = program[self.ip + 1];
-            use Instructions::*;
-            match Instructions::decode(*opcode).expect("Invalid instruction") {
-                Adv => {
-                    let num = self.areg;
-                    let den = 2_usize.pow(self.combo(operand) as u32);
-                    self.areg = num / den;
-                    self.ip += 2;
-                }
-                Bxl => {
-                    self.breg ^= operand;
-                    self.ip += 2;
-                }
-                Bst => {
-                    self.breg = self.combo(operand) % 8;
+    /// Executes the instruction at `ip`. Returns `false` once the program has halted, which
+    /// includes an `ip` pointing at the last opcode, without an operand after it.
+    fn step(&mut self, program: &[usize], output: &mut Vec<usize>) -> Result<bool, String> {
+        let (Some(&opcode), Some(&operand)) = (program.get(self.ip), program.get(self.ip + 1))
+        else {
+            return Ok(false);
+        };
+        let Some(instruction) = Instructions::decode(opcode) else {
+            return Err(format!(
+                "instruction at {} has the invalid opcode {opcode}",
+                self.ip
+            ));
+        };
+        use Instructions::*;
+        match instruction {
+            Adv => {
+                self.areg = shr(self.areg, self.combo(operand)?);
+                self.ip += 2;
+            }
+            Bxl => {
+                self.breg ^= operand;
+                self.ip += 2;
+            }
+            Bst => {
+                self.breg = self.combo(operand)? % 8;
+                self.ip += 2;
+            }
+            Jnz => {
+                if self.areg == 0 {
                     self.ip += 2;
+                } else {
+                    self.ip = operand;
                 }
-                Jnz => {
-                    if self.areg == 0 {
-                        self.ip += 2;
-                    } else {
-                        self.ip = operand;
+            }
+            Bxc => {
+                self.breg ^= self.creg;
+                self.ip += 2;
+            }
+            Out => {
+                let value = self.combo(operand)? % 8;
+                output.push(value);
+                self.ip += 2;
+            }
+            Bdv => {
+                self.breg = shr(self.areg, self.combo(operand)?);
+                self.ip += 2;
+            }
+            Cdv => {
+                self.creg = shr(self.areg, self.combo(operand)?);
+                self.ip += 2;
+            }
+        }
+        Ok(true)
+    }
+
+    fn execute(&mut self, program: &[usize]) -> Result<Vec<usize>, String> {
+        let mut output = Vec::new();
+        while self.step(program, &mut output)? {}
+        Ok(output)
+    }
+
+    /// The first output, or `None` if the program halts or fails before printing anything.
+    fn first_output(&mut self, program: &[usize]) -> Option<usize> {
+        let mut output = Vec::new();
+        while output.is_empty() && self.step(program, &mut output).ok()? {}
+        output.first().copied()
+    }
+
+    fn registers(&self) -> [usize; 3] {
+        [self.areg, self.breg, self.creg]
+    }
+}
+
+impl Display for Cpu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "A={} B={} C={} ip={}",
+            self.areg, self.breg, self.creg, self.ip
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A combo operand, resolved when compiling.
+#[derive(Clone, Copy, Debug)]
+enum Operand {
+    Literal(usize),
+    A,
+    B,
+    C,
+}
+
+impl Operand {
+    fn value(self, a: usize, b: usize, c: usize) -> usize {
+        match self {
+            Operand::Literal(n) => n,
+            Operand::A => a,
+            Operand::B => b,
+            Operand::C => c,
+        }
+    }
+}
+
+/// An instruction with a decoded operand. Jump targets are indices into [`Compiled::ops`].
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Adv(Operand),
+    Bxl(usize),
+    Bst(Operand),
+    Jnz(usize),
+    Bxc,
+    Out(Operand),
+    Bdv(Operand),
+    Cdv(Operand),
+}
+
+/// `value / 2^shift`, which is 0 for shifts beyond the width of `usize`.
+fn shr(value: usize, shift: usize) -> usize {
+    u32::try_from(shift)
+        .ok()
+        .and_then(|shift| value.checked_shr(shift))
+        .unwrap_or(0)
+}
+
+/// Why [`Compiled::new`] rejects a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CompileError {
+    /// The program is invalid, and fails in the interpreter as well.
+    Invalid(String),
+    /// The program is valid, but only the interpreter can run it.
+    Unsupported(String),
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompileError::Invalid(reason) | CompileError::Unsupported(reason) => {
+                write!(f, "{reason}")
+            }
+        }
+    }
+}
+
+impl From<CompileError> for String {
+    fn from(err: CompileError) -> Self {
+        err.to_string()
+    }
+}
+
+/// A program decoded ahead of time, so running it is only register arithmetic. Unlike
+/// [`Cpu::execute`], it can stop as soon as the output is of no more interest.
+struct Compiled {
+    ops: Vec<Op>,
+}
+
+impl Compiled {
+    /// Fails for invalid instructions, and for jumps into the middle of an instruction.
+    fn new(program: &[usize]) -> Result<Self, CompileError> {
+        if !program.len().is_multiple_of(2) {
+            return Err(CompileError::Unsupported(
+                "the program ends with an opcode without operand".to_string(),
+            ));
+        }
+        let ops = program
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(i, instruction)| {
+                let (opcode, operand) = (instruction[0], instruction[1]);
+                let combo = || match operand {
+                    0..=3 => Ok(Operand::Literal(operand)),
+                    4 => Ok(Operand::A),
+                    5 => Ok(Operand::B),
+                    6 => Ok(Operand::C),
+                    _ => Err(CompileError::Invalid(format!(
+                        "instruction {i} uses the reserved combo operand 7"
+                    ))),
+                };
+                let Some(decoded) = Instructions::decode(opcode) else {
+                    return Err(CompileError::Invalid(format!(
+                        "instruction {i} has the invalid opcode {opcode}"
+                    )));
+                };
+                use Instructions::*;
+                Ok(match decoded {
+                    Adv => Op::Adv(combo()?),
+                    Bxl => Op::Bxl(operand),
+                    Bst => Op::Bst(combo()?),
+                    Jnz if operand.is_multiple_of(2) => Op::Jnz(operand / 2),
+                    Jnz => {
+                        return Err(CompileError::Unsupported(format!(
+                            "instruction {i} jumps into the middle of an instruction"
+                        )))
+                    }
+                    Bxc => Op::Bxc,
+                    Out => Op::Out(combo()?),
+                    Bdv => Op::Bdv(combo()?),
+                    Cdv => Op::Cdv(combo()?),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { ops })
+    }
+
+    /// Runs the program from the registers A, B and C, passing every output to `emit` until it
+    /// returns `false`.
+    fn run(&self, [mut a, mut b, mut c]: [usize; 3], mut emit: impl FnMut(usize) -> bool) {
+        let mut pc = 0;
+        while let Some(&op) = self.ops.get(pc) {
+            pc += 1;
+            match op {
+                Op::Adv(x) => a = shr(a, x.value(a, b, c)),
+                Op::Bxl(n) => b ^= n,
+                Op::Bst(x) => b = x.value(a, b, c) % 8,
+                Op::Jnz(target) => {
+                    if a != 0 {
+                        pc = target;
                     }
                 }
-                Bxc => {
-                    self.breg ^= self.creg;
-                    self.ip += 2;
-                }
-                Out => {
-                    let value = self.combo(operand) % 8;
-                    output.push(value);
-                    self.ip += 2;
-                }
-                Bdv => {
-                    let num = self.areg;
-                    let den = 2_usize.pow(self.combo(operand) as u32);
-                    self.breg = num / den;
-                    self.ip += 2;
-                }
-                Cdv => {
-                    let num = self.areg;
-                    let den = 2_usize.pow(self.combo(operand) as u32);
-                    self.creg = num / den;
-                    self.ip += 2;
+                Op::Bxc => b ^= c,
+                Op::Out(x) => {
+                    if !emit(x.value(a, b, c) % 8) {
+                        return;
+                    }
                 }
+                Op::Bdv(x) => b = shr(a, x.value(a, b, c)),
+                Op::Cdv(x) => c = shr(a, x.value(a, b, c)),
             }
         }
+    }
+
+    fn execute(&self, registers: [usize; 3]) -> Vec<usize> {
+        let mut output = Vec::new();
+        self.run(registers, |value| {
+            output.push(value);
+            true
+        });
         output
     }
+
+    /// How many outputs match the start of `target`. Stops at the first one that does not.
+    fn matching_prefix(&self, registers: [usize; 3], target: &[usize]) -> usize {
+        let mut matching = 0;
+        self.run(registers, |value| {
+            if target.get(matching) != Some(&value) {
+                return false;
+            }
+            matching += 1;
+            true
+        });
+        matching
+    }
+
+    /// The first output when starting with `areg` and the other registers cleared.
+    fn first_output(&self, areg: usize) -> Option<usize> {
+        let mut first = None;
+        self.run([areg, 0, 0], |value| {
+            first = Some(value);
+            false
+        });
+        first
+    }
 }
 
-impl From<&str> for Cpu {
-    fn from(value: &str) -> Self {
-        let mut lines = value.lines();
-        let areg_str = lines.next().unwrap().strip_prefix("Register A: ").unwrap();
-        let breg_str = lines.next().unwrap().strip_prefix("Register B: ").unwrap();
-        let creg_str = lines.next().unwrap().strip_prefix("Register C: ").unwrap();
-        let areg = areg_str.parse().unwrap();
-        let breg = breg_str.parse().unwrap();
-        let creg = creg_str.parse().unwrap();
-        let ip = 0;
-        Self {
+impl Cpu {
+    fn parse(input: &parse::Input, text: &str) -> Result<Self, ParseError> {
+        let mut lines = text.lines();
+        let mut registers = [0; 3];
+        for (register, name) in registers.iter_mut().zip(["A", "B", "C"]) {
+            let prefix = format!("Register {name}: ");
+            let line = lines.next().ok_or_else(|| {
+                input.error(&text[text.len()..], format!("expected \"{prefix}\""))
+            })?;
+            *register = input.parse(input.strip_prefix(line, &prefix)?)?;
+        }
+        let [areg, breg, creg] = registers;
+        Ok(Self {
             areg,
             breg,
             creg,
-            ip,
-        }
+            ip: 0,
+        })
     }
 }
 
@@ -132,63 +422,411 @@ struct Input {
     program: Vec<usize>,
 }
 
-impl From<&str> for Input {
-    fn from(value: &str) -> Self {
-        let (cpu_str, program_str) = value.split_once("\n\n").unwrap();
-        let cpu: Cpu = cpu_str.into();
-        let program: Vec<_> = program_str
-            .strip_prefix("Program: ")
-            .unwrap()
-            .split(",")
-            .map(|n| n.parse().unwrap())
-            .collect();
-        Self { cpu, program }
+impl Input {
+    fn parse(text: &str) -> Result<Self, ParseError> {
+        let input = parse::Input::new(text);
+        let mut sections = input.sections();
+        let (cpu_str, program_str) = match (sections.next(), sections.next()) {
+            (Some(cpu), Some(program)) => (cpu, program),
+            _ => return Err(input.error(text, "expected registers and a program")),
+        };
+        let cpu = Cpu::parse(&input, cpu_str)?;
+        let program = input
+            .strip_prefix(program_str, "Program: ")?
+            .split(',')
+            .map(|n| input.parse(n))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { cpu, program })
+    }
+}
+
+pub fn part_one(input: &str) -> Result<String, String> {
+    let Input { mut cpu, program } = Input::parse(input).map_err(|err| err.to_string())?;
+    let output = match Compiled::new(&program) {
+        Ok(compiled) => compiled.execute(cpu.registers()),
+        Err(CompileError::Unsupported(_)) => cpu.execute(&program)?,
+        Err(err) => return Err(err.into()),
+    };
+    Ok(format_output(&output))
+}
+
+/// Checks that `program` is a single loop that shifts A by 3 bits and prints one value per
+/// pass, computed from A alone. Only then each output depends on 3 more bits of A than the next.
+fn check_loop(program: &[usize]) -> Result<(), String> {
+    if !program.len().is_multiple_of(2) || !program.ends_with(&[3, 0]) {
+        return Err("the program must end with `jnz 0`".to_string());
+    }
+
+    let (mut shifts, mut outputs) = (0, 0);
+    let (mut breg_set, mut creg_set) = (false, false);
+    let body = &program[..program.len() - 2];
+    for (i, instruction) in body.chunks_exact(2).enumerate() {
+        let (opcode, operand) = (instruction[0], instruction[1]);
+        let Some(decoded) = Instructions::decode(opcode) else {
+            return Err(format!("instruction {i} has the invalid opcode {opcode}"));
+        };
+
+        use Instructions::*;
+        let combo = matches!(decoded, Adv | Bst | Out | Bdv | Cdv);
+        if combo && operand == 7 {
+            return Err(format!("instruction {i} uses the reserved combo operand 7"));
+        }
+        let reads_breg = matches!(decoded, Bxl | Bxc) || (combo && operand == 5);
+        let reads_creg = matches!(decoded, Bxc) || (combo && operand == 6);
+        if (reads_breg && !breg_set) || (reads_creg && !creg_set) {
+            return Err(format!(
+                "instruction {i} reads a register left over from the previous pass"
+            ));
+        }
+
+        match decoded {
+            Adv if operand == 3 => shifts += 1,
+            Adv => return Err(format!("instruction {i} shifts A by other than 3 bits")),
+            Jnz => return Err(format!("instruction {i} jumps before the end of the loop")),
+            Out => outputs += 1,
+            Bxl | Bst | Bxc | Bdv => breg_set = true,
+            Cdv => creg_set = true,
+        }
+    }
+
+    match (shifts, outputs) {
+        (1, 1) => Ok(()),
+        (1, _) => Err(format!("the loop prints {outputs} values instead of 1")),
+        _ => Err(format!("the loop shifts A {shifts} times instead of once")),
+    }
+}
+
+/// The lowest value of A that makes `program` print itself.
+fn solve_quine(program: &[usize]) -> Result<usize, String> {
+    check_loop(program)?;
+    let compiled = Compiled::new(program)?;
+    find_quine(program, |areg| compiled.first_output(areg))
+}
+
+/// Same as [`solve_quine`], but running the program on the interpreter.
+fn solve_quine_interpreted(program: &[usize]) -> Result<usize, String> {
+    check_loop(program)?;
+    find_quine(program, |areg| {
+        Cpu {
+            areg,
+            ..Cpu::default()
+        }
+        .first_output(program)
+    })
+}
+
+/// Searches A for a `program` that passed [`check_loop`], given its first output for a value of A.
+///
+/// The last pass runs with the 3 highest bits of A, and every pass before adds the next 3 bits.
+/// So this picks the bits for the last output first, and backtracks if no choice of the bits for
+/// an earlier output works out.
+fn find_quine(
+    program: &[usize],
+    first_output: impl Fn(usize) -> Option<usize>,
+) -> Result<usize, String> {
+    fn search(
+        first_output: &impl Fn(usize) -> Option<usize>,
+        program: &[usize],
+        pass: usize,
+        higher_bits: usize,
+    ) -> Option<usize> {
+        // A is non-zero in every pass but the first, or the loop would have ended before.
+        let first_bits = usize::from(pass > 0 && higher_bits == 0);
+        (first_bits..8).find_map(|bits| {
+            let areg = higher_bits << 3 | bits;
+            if first_output(areg) != Some(program[pass]) {
+                return None;
+            }
+            match pass.checked_sub(1) {
+                Some(previous) => search(first_output, program, previous, areg),
+                None => Some(areg),
+            }
+        })
+    }
+
+    search(&first_output, program, program.len() - 1, 0)
+        .ok_or_else(|| "no value of register A makes the program print itself".to_string())
+}
+
+pub fn part_two(input: &str) -> Result<usize, String> {
+    let Input { program, .. } = Input::parse(input).map_err(|err| err.to_string())?;
+    solve_quine(&program)
+}
+
+fn part_one_interpreted(input: &str) -> Result<String, String> {
+    let Input { mut cpu, program } = Input::parse(input).map_err(|err| err.to_string())?;
+    Ok(format_output(&cpu.execute(&program)?))
+}
+
+fn part_two_interpreted(input: &str) -> Result<usize, String> {
+    let Input { program, .. } = Input::parse(input).map_err(|err| err.to_string())?;
+    solve_quine_interpreted(&program)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Runs both parts on the interpreter instead of the compiled program, so that
+/// `cargo time 17 --interpreter` benches the one to compare against `cargo time 17`.
+fn interpret(input: &str) {
+    run_part(part_one_interpreted, input, DAY, 1);
+    run_part(part_two_interpreted, input, DAY, 2);
+}
+
+fn print_disassembly(input: &str) {
+    let Input { program, .. } = match Input::parse(input) {
+        Ok(input) => input,
+        Err(err) => return eprintln!("{err}"),
+    };
+    for ip in (0..program.len()).step_by(2) {
+        println!("{}", disassemble(&program, ip));
     }
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let Input { mut cpu, program } = input.into();
-    let output: Vec<_> = cpu
-        .execute(&program)
-        .into_iter()
-        .map(|n| n.to_string())
-        .collect();
-    Some(output.join(","))
+/// Runs the program like part one, printing every instruction with the registers after it.
+fn trace(input: &str) {
+    let Input { mut cpu, program } = match Input::parse(input) {
+        Ok(input) => input,
+        Err(err) => return eprintln!("{err}"),
+    };
+    let mut output = Vec::new();
+    loop {
+        let ip = cpu.ip;
+        match cpu.step(&program, &mut output) {
+            Ok(true) => println!("{:<32} {cpu}", disassemble(&program, ip)),
+            Ok(false) => break,
+            Err(err) => {
+                println!("error: {err}");
+                break;
+            }
+        }
+    }
+    println!("output: {}", format_output(&output));
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let Input { mut cpu, program } = input.into();
-    let mut lower = 0;
-    for i in 0..program.len() - 1 {
-        let offset = program.len() - i;
-        let stride = 10_usize.pow(offset as u32 - 2); // -2 found by inspecting resulting output size
-        let mut areg = lower;
-        loop {
+/// Times the interpreter against the compiled program on a brute force over A, counting how much
+/// of each output matches the program.
+fn compare(input: &str) {
+    const RUNS: usize = 1_000_000;
+    let Input { mut cpu, program } = match Input::parse(input) {
+        Ok(input) => input,
+        Err(err) => return eprintln!("{err}"),
+    };
+    let compiled = match Compiled::new(&program) {
+        Ok(compiled) => compiled,
+        Err(err) => return println!("cannot compile the program: {err}"),
+    };
+    let areg_range = cpu.areg..cpu.areg.saturating_add(RUNS);
+    let runs = areg_range.len();
+
+    let timer = Instant::now();
+    let interpreted: usize = areg_range
+        .clone()
+        .map(|areg| {
             cpu.reset(areg);
-            let output = cpu.execute(&program);
-            match output.len().cmp(&program.len()) {
-                Ordering::Equal => {
-                    if output[offset..] == program[offset..] {
-                        break;
-                    }
-                    lower = areg;
+            let output = cpu.execute(&program).expect("the program compiled");
+            output
+                .iter()
+                .zip(&program)
+                .take_while(|(a, b)| a == b)
+                .count()
+        })
+        .sum();
+    let interpreter_time = timer.elapsed();
+
+    let timer = Instant::now();
+    let compiled_matches: usize = areg_range
+        .map(|areg| compiled.matching_prefix([areg, 0, 0], &program))
+        .sum();
+    let compiled_time = timer.elapsed();
+
+    assert_eq!(interpreted, compiled_matches, "the outputs differ");
+    println!("{runs} runs, {interpreted} matching outputs");
+    println!("interpreter: {interpreter_time:?}");
+    println!(
+        "compiled:    {compiled_time:?} ({:.1}x)",
+        interpreter_time.as_secs_f64() / compiled_time.as_secs_f64()
+    );
+}
+
+const HELP: &str = "\
+s [n]          step n instructions, 1 by default (or an empty line)
+c              continue until a breakpoint or the end
+b <ip>         toggle a breakpoint
+set <reg> <n>  set register a, b, c or ip
+r              show registers and output
+l              list the program
+reset [a]      restart, optionally with another value of A
+q              quit";
+
+/// Continuing stops after this many instructions, in case the program never halts.
+const MAX_STEPS: usize = 10_000_000;
+
+struct Debugger {
+    initial: Cpu,
+    cpu: Cpu,
+    program: Vec<usize>,
+    breakpoints: BTreeSet<usize>,
+    output: Vec<usize>,
+}
+
+impl Debugger {
+    fn new(cpu: Cpu, program: Vec<usize>) -> Self {
+        Self {
+            initial: cpu.clone(),
+            cpu,
+            program,
+            breakpoints: BTreeSet::new(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Runs one command. Returns what to print, or `Break` to quit.
+    fn command(&mut self, line: &str) -> ControlFlow<(), String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let result = match words.as_slice() {
+            [] | ["s"] => Ok(self.steps(1)),
+            ["s", n] => parse_number(n).map(|n| self.steps(n)),
+            ["c"] => Ok(self.resume()),
+            ["b", ip] => parse_number(ip).map(|ip| self.toggle_breakpoint(ip)),
+            ["set", register, value] => {
+                parse_number(value).and_then(|value| self.set(register, value))
+            }
+            ["r"] => Ok(self.status()),
+            ["l"] => Ok(self.listing()),
+            ["reset"] => Ok(self.reset(self.initial.areg)),
+            ["reset", areg] => parse_number(areg).map(|areg| self.reset(areg)),
+            ["q"] => return ControlFlow::Break(()),
+            ["h"] => Ok(HELP.to_string()),
+            _ => Err(format!("unknown command `{line}`, `h` lists them")),
+        };
+        ControlFlow::Continue(result.unwrap_or_else(|err| err))
+    }
+
+    fn step(&mut self) -> Result<bool, String> {
+        self.cpu.step(&self.program, &mut self.output)
+    }
+
+    fn steps(&mut self, n: usize) -> String {
+        let mut lines = Vec::new();
+        for _ in 0..n {
+            let ip = self.cpu.ip;
+            match self.step() {
+                Ok(true) => {}
+                Ok(false) => {
+                    lines.push(self.halted());
+                    break;
+                }
+                Err(err) => {
+                    lines.push(format!("error: {err}"));
+                    break;
                 }
-                Ordering::Less => lower = areg,
-                Ordering::Greater => break,
             }
-            areg += stride;
+            lines.push(format!(
+                "{:<32} {}",
+                disassemble(&self.program, ip),
+                self.cpu
+            ));
         }
+        lines.join("\n")
     }
 
-    for areg in lower.. {
-        cpu.reset(areg);
-        let output = cpu.execute(&program);
-        if output == program {
-            return Some(areg);
+    fn resume(&mut self) -> String {
+        for _ in 0..MAX_STEPS {
+            match self.step() {
+                Ok(true) => {}
+                Ok(false) => return self.halted(),
+                Err(err) => return format!("error: {err}\n{}", self.status()),
+            }
+            if self.breakpoints.contains(&self.cpu.ip) {
+                return format!("breakpoint at {}\n{}", self.cpu.ip, self.status());
+            }
         }
+        format!(
+            "still running after {MAX_STEPS} instructions\n{}",
+            self.status()
+        )
+    }
+
+    fn toggle_breakpoint(&mut self, ip: usize) -> String {
+        if self.breakpoints.remove(&ip) {
+            format!("removed breakpoint at {ip}")
+        } else {
+            self.breakpoints.insert(ip);
+            format!("breakpoint at {ip}")
+        }
+    }
+
+    fn set(&mut self, register: &str, value: usize) -> Result<String, String> {
+        match register {
+            "a" => self.cpu.areg = value,
+            "b" => self.cpu.breg = value,
+            "c" => self.cpu.creg = value,
+            "ip" => self.cpu.ip = value,
+            _ => return Err(format!("unknown register `{register}`")),
+        }
+        Ok(self.status())
+    }
+
+    fn reset(&mut self, areg: usize) -> String {
+        self.cpu = Cpu {
+            areg,
+            ..self.initial.clone()
+        };
+        self.output.clear();
+        self.status()
+    }
+
+    fn status(&self) -> String {
+        format!("{}\noutput: {}", self.cpu, format_output(&self.output))
     }
 
-    None
+    fn halted(&self) -> String {
+        format!("halted\noutput: {}", format_output(&self.output))
+    }
+
+    /// The program with `>` at the instruction pointer and `*` at breakpoints.
+    fn listing(&self) -> String {
+        let lines: Vec<_> = (0..self.program.len())
+            .step_by(2)
+            .map(|ip| {
+                let at_ip = if ip == self.cpu.ip { '>' } else { ' ' };
+                let breakpoint = if self.breakpoints.contains(&ip) {
+                    '*'
+                } else {
+                    ' '
+                };
+                format!("{at_ip}{breakpoint}{}", disassemble(&self.program, ip))
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+fn parse_number(text: &str) -> Result<usize, String> {
+    text.parse()
+        .map_err(|err| format!("cannot parse `{text}`: {err}"))
+}
+
+fn debug(input: &str) {
+    let Input { cpu, program } = match Input::parse(input) {
+        Ok(input) => input,
+        Err(err) => return eprintln!("{err}"),
+    };
+    let mut debugger = Debugger::new(cpu, program);
+    println!("{}\n`h` lists the commands", debugger.listing());
+
+    let mut lines = io::stdin().lines();
+    loop {
+        print!("(debug) ");
+        let _ = io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        match debugger.command(&line) {
+            ControlFlow::Continue(text) => println!("{text}"),
+            ControlFlow::Break(()) => break,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -200,7 +838,148 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some("4,6,3,5,6,3,5,2,1,0".to_string()));
+        assert_eq!(result, Ok("4,6,3,5,6,3,5,2,1,0".to_string()));
+    }
+
+    #[test]
+    fn test_part_one_falls_back_to_interpreter() {
+        let input =
+            |program| format!("Register A: 5\nRegister B: 0\nRegister C: 0\n\nProgram: {program}");
+        // jumps to index 1, which reads as `bxl 5`, `bxc 0`, `out 5` from there.
+        assert_eq!(part_one(&input("3,1,5,4,0,5,5,0")), Ok("5".to_string()));
+        assert_eq!(
+            part_one(&input("0,7,5,4,3,0")),
+            Err("instruction 0 uses the reserved combo operand 7".to_string())
+        );
+        assert_eq!(
+            part_one(&input("8,0")),
+            Err("instruction 0 has the invalid opcode 8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_compiled_matches_interpreter() {
+        let programs = [
+            vec![0, 1, 5, 4, 3, 0],
+            vec![0, 3, 5, 4, 3, 0],
+            vec![2, 4, 1, 1, 7, 5, 1, 5, 4, 3, 0, 3, 5, 5, 3, 0],
+            vec![2, 4, 1, 3, 7, 5, 4, 1, 1, 3, 0, 3, 5, 5, 3, 0],
+        ];
+        for program in programs {
+            let compiled = Compiled::new(&program).unwrap();
+            for areg in (0..100_000).step_by(37).chain([1 << 40, usize::MAX]) {
+                let mut cpu = Cpu {
+                    areg,
+                    ..Cpu::default()
+                };
+                let expected = cpu.execute(&program).unwrap();
+                assert_eq!(compiled.execute([areg, 0, 0]), expected, "A = {areg}");
+                assert_eq!(compiled.first_output(areg), expected.first().copied());
+            }
+        }
+    }
+
+    #[test]
+    fn test_stops_at_divergence() {
+        let compiled = Compiled::new(&[0, 1, 5, 4, 3, 0]).unwrap();
+        assert_eq!(compiled.matching_prefix([729, 0, 0], &[4, 6, 3, 9]), 3);
+        assert_eq!(compiled.matching_prefix([729, 0, 0], &[4, 6]), 2);
+        assert_eq!(compiled.matching_prefix([729, 0, 0], &[5]), 0);
+        assert_eq!(
+            Compiled::new(&[0, 3, 3, 1]).err().unwrap(),
+            CompileError::Unsupported(
+                "instruction 1 jumps into the middle of an instruction".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = [2, 4, 1, 3, 7, 5, 0, 3, 5, 5, 3, 0];
+        let lines: Vec<_> = (0..program.len())
+            .step_by(2)
+            .map(|ip| disassemble(&program, ip))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "  0: bst 4  B = A % 8",
+                "  2: bxl 3  B = B ^ 3",
+                "  4: cdv 5  C = A / 2^B",
+                "  6: adv 3  A = A / 2^3",
+                "  8: out 5  print B % 8",
+                " 10: jnz 0  jump to 0 if A != 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debugger() {
+        let Input { cpu, program } = Input::parse(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ))
+        .unwrap();
+        let mut debugger = Debugger::new(cpu, program);
+        let mut run = |line| match debugger.command(line) {
+            ControlFlow::Continue(text) => text,
+            ControlFlow::Break(()) => "quit".to_string(),
+        };
+
+        assert_eq!(
+            run("s"),
+            "  0: adv 1  A = A / 2^1          A=364 B=0 C=0 ip=2"
+        );
+        assert_eq!(run("b 4"), "breakpoint at 4");
+        assert_eq!(run("c"), "breakpoint at 4\nA=364 B=0 C=0 ip=4\noutput: 4");
+        assert_eq!(run("set a 0"), "A=0 B=0 C=0 ip=4\noutput: 4");
+        assert_eq!(run("c"), "halted\noutput: 4");
+        assert_eq!(run("reset"), "A=729 B=0 C=0 ip=0\noutput: ");
+        assert_eq!(run("b 4"), "removed breakpoint at 4");
+        assert_eq!(run("c"), "halted\noutput: 4,6,3,5,6,3,5,2,1,0");
+        run("reset");
+        run("b 2");
+        assert_eq!(
+            run("l"),
+            ">   0: adv 1  A = A / 2^1\n *  2: out 4  print A % 8\n    4: jnz 0  jump to 0 if A != 0"
+        );
+        assert_eq!(run("set d 1"), "unknown register `d`");
+        assert_eq!(run("q"), "quit");
+    }
+
+    #[test]
+    fn test_debugger_survives_edits() {
+        let mut debugger = Debugger::new(
+            Cpu {
+                areg: 729,
+                ..Cpu::default()
+            },
+            vec![6, 5, 5, 5, 3, 0],
+        );
+        let mut run = |line| match debugger.command(line) {
+            ControlFlow::Continue(text) => text,
+            ControlFlow::Break(()) => "quit".to_string(),
+        };
+
+        run("set b 200");
+        assert!(run("s").ends_with("A=729 B=0 C=0 ip=2"));
+        run("set ip 5");
+        assert_eq!(run("s"), "halted\noutput: ");
+
+        let mut debugger = Debugger::new(Cpu::default(), vec![0, 7, 5, 4, 3, 0]);
+        assert_eq!(
+            debugger.command("s"),
+            ControlFlow::Continue(
+                "error: instruction at 0 uses the reserved combo operand 7".to_string()
+            )
+        );
+        let mut debugger = Debugger::new(Cpu::default(), vec![8, 0]);
+        assert_eq!(
+            debugger.command("c"),
+            ControlFlow::Continue(
+                "error: instruction at 0 has the invalid opcode 8\nA=0 B=0 C=0 ip=0\noutput: "
+                    .to_string()
+            )
+        );
     }
 
     #[test]
@@ -208,6 +987,82 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(117440));
+        assert_eq!(result, Ok(117440));
+    }
+
+    #[test]
+    fn test_interpreted_parts() {
+        let example = |part| advent_of_code::template::read_file_part("examples", DAY, part);
+        assert_eq!(part_one_interpreted(&example(1)), part_one(&example(1)));
+        assert_eq!(part_two_interpreted(&example(2)), part_two(&example(2)));
+    }
+
+    fn run(program: &[usize], areg: usize) -> Vec<usize> {
+        let mut cpu = Cpu {
+            areg,
+            ..Cpu::default()
+        };
+        cpu.execute(program).unwrap()
+    }
+
+    #[test]
+    fn test_solve_synthetic_quines() {
+        // the usual shape of the puzzle inputs, with varying constants and order.
+        let mut solved = 0;
+        for k1 in 0..8 {
+            for k2 in 0..8 {
+                for program in [
+                    vec![2, 4, 1, k1, 7, 5, 1, k2, 4, 3, 0, 3, 5, 5, 3, 0],
+                    vec![2, 4, 1, k1, 7, 5, 4, 1, 1, k2, 5, 5, 0, 3, 3, 0],
+                    vec![2, 4, 1, k1, 7, 5, 0, 3, 4, k2, 1, k2, 5, 5, 3, 0],
+                ] {
+                    assert_eq!(solve_quine_interpreted(&program), solve_quine(&program));
+                    match solve_quine(&program) {
+                        Ok(areg) => {
+                            assert_eq!(run(&program, areg), program, "A = {areg}");
+                            solved += 1;
+                        }
+                        Err(err) => {
+                            assert_eq!(err, "no value of register A makes the program print itself")
+                        }
+                    }
+                }
+            }
+        }
+        assert!(solved >= 10, "only {solved} programs solved");
+    }
+
+    #[test]
+    fn test_finds_lowest_register_value() {
+        // the example is short enough to try every smaller value.
+        let program = [0, 3, 5, 4, 3, 0];
+        let areg = solve_quine(&program).unwrap();
+        assert!((0..areg).all(|a| run(&program, a) != program));
+    }
+
+    #[test]
+    fn test_rejects_other_programs() {
+        let err = |program: &[usize]| solve_quine(program).unwrap_err();
+        assert_eq!(err(&[0, 3, 5, 4]), "the program must end with `jnz 0`");
+        assert_eq!(
+            err(&[0, 3, 5, 4, 5, 4, 3, 0]),
+            "the loop prints 2 values instead of 1"
+        );
+        assert_eq!(
+            err(&[0, 1, 5, 4, 3, 0]),
+            "instruction 0 shifts A by other than 3 bits"
+        );
+        assert_eq!(
+            err(&[5, 4, 3, 0]),
+            "the loop shifts A 0 times instead of once"
+        );
+        assert_eq!(
+            err(&[0, 3, 1, 2, 5, 5, 3, 0]),
+            "instruction 1 reads a register left over from the previous pass"
+        );
+        assert_eq!(
+            err(&[0, 3, 3, 0, 5, 4, 3, 0]),
+            "instruction 1 jumps before the end of the loop"
+        );
     }
 }
@@ -1,106 +1,292 @@
+use advent_of_code::{
+    dsu::OpenCells,
+    geom::Coord,
+    grid::{BitGrid, GridMap},
+    parse::{Input, ParseError},
+    search,
+    template::arg_value,
+};
 use rayon::prelude::*;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::time::Instant;
 
-advent_of_code::solution!(18);
+advent_of_code::solution!(18;
+    "--method" => solve_with,
+    "--compare" => compare,
+);
 
-const DIM: usize = 70;
+/// Side length of the memory space of the puzzle input.
+const SIZE: usize = 71;
+/// Number of bytes that fell before part one.
+const FALLEN: usize = 1024;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-struct Coord(usize, usize);
+fn parse_coord(input: &Input, line: &str) -> Result<Coord, ParseError> {
+    let (x_str, y_str) = input.split_once(line, ",")?;
+    Ok(Coord::new(input.parse(x_str)?, input.parse(y_str)?))
+}
 
-impl Coord {
-    fn neighbors(&self) -> impl Iterator<Item = Self> {
-        let Coord(x, y) = *self;
-        let mut list = Vec::new();
-        if x > 0 {
-            list.push(Self(x - 1, y));
-        }
-        if x < DIM {
-            list.push(Self(x + 1, y));
-        }
-        if y > 0 {
-            list.push(Self(x, y - 1));
-        }
-        if y < DIM {
-            list.push(Self(x, y + 1));
+/// How part two finds the first byte cutting the start off from the exit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    /// A search after every byte, all of them in parallel.
+    Scan,
+    /// Binary search over the number of fallen bytes, one search per halving.
+    Bisect,
+    /// Takes the bytes away again, latest first, joining the free cells in a union-find until
+    /// start and exit are connected.
+    Reverse,
+}
+
+impl Method {
+    const ALL: [Method; 3] = [Method::Scan, Method::Bisect, Method::Reverse];
+
+    fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|method| method.name() == name)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Method::Scan => "scan",
+            Method::Bisect => "bisect",
+            Method::Reverse => "reverse",
         }
-        list.into_iter()
     }
 }
 
-impl From<&str> for Coord {
-    fn from(value: &str) -> Self {
-        let (x_str, y_str) = value.split_once(",").unwrap();
-        let x = x_str.parse().unwrap();
-        let y = y_str.parse().unwrap();
-        Self(x, y)
-    }
+/// A square memory space of `size` x `size` cells and the bytes falling into it, in order.
+struct Memory {
+    size: usize,
+    bytes: Vec<Coord>,
 }
 
-const START: Coord = Coord(0, 0);
-const STOP: Coord = Coord(DIM, DIM);
+impl Memory {
+    /// Reads the bytes, which must fall inside the memory space.
+    fn parse(input: &str, size: usize) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let mut memory = Self {
+            size,
+            bytes: Vec::new(),
+        };
+        for line in input.as_str().lines() {
+            let coord = parse_coord(&input, line)?;
+            if !memory.contains(&coord) {
+                return Err(input.error(line, format!("outside of the {size}x{size} memory space")));
+            }
+            memory.bytes.push(coord);
+        }
+        Ok(memory)
+    }
 
-#[derive(Eq, PartialEq)]
-struct State {
-    cost: usize,
-    pos: Coord,
-}
+    fn start(&self) -> Coord {
+        Coord::new(0, 0)
+    }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&self.cost)
+    fn exit(&self) -> Coord {
+        let last = self.size as isize - 1;
+        Coord::new(last, last)
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    fn contains(&self, coord: &Coord) -> bool {
+        let range = 0..self.size as isize;
+        range.contains(&coord.x) && range.contains(&coord.y)
     }
-}
 
-fn shortest_path(corruptions: &[Coord]) -> Option<usize> {
-    let corruptions: HashSet<Coord> = HashSet::from_iter(corruptions.iter().copied());
-    let mut ledger = HashMap::new();
-    let mut queue = BinaryHeap::new();
-    queue.push(State {
-        cost: 0,
-        pos: START,
-    });
-    while let Some(State { cost, pos }) = queue.pop() {
-        if pos == STOP {
-            return Some(cost);
+    /// Steps from the start to the exit once the first `fallen` bytes have fallen.
+    fn shortest_path(&self, fallen: usize) -> Option<usize> {
+        let mut corrupted = BitGrid::new(self.size, self.size);
+        corrupted.extend(self.bytes[..fallen].iter().copied());
+        let exit = self.exit();
+        let search = search::bfs_in(
+            GridMap::new(self.size, self.size),
+            [self.start()],
+            |pos| {
+                pos.neighbors4()
+                    .filter(|coord| self.contains(coord) && !corrupted.contains(coord))
+            },
+            |&pos| pos == exit,
+        );
+        search.distance(&exit)
+    }
+
+    /// The first byte after which the exit cannot be reached, if any.
+    fn first_blocking_byte(&self, method: Method) -> Option<Coord> {
+        match method {
+            Method::Scan => self.scan(),
+            Method::Bisect => self.bisect(),
+            Method::Reverse => self.reverse(),
         }
+    }
 
-        let stored_cost = *ledger.get(&pos).unwrap_or(&usize::MAX);
-        if cost > stored_cost {
-            continue;
+    fn scan(&self) -> Option<Coord> {
+        (1..=self.bytes.len())
+            .into_par_iter()
+            .find_first(|&fallen| self.shortest_path(fallen).is_none())
+            .map(|fallen| self.bytes[fallen - 1])
+    }
+
+    fn bisect(&self) -> Option<Coord> {
+        self.shortest_path(0)?;
+        // the exit is reachable after `lo` bytes and, unless `hi` is past the end, not after `hi`.
+        let (mut lo, mut hi) = (0, self.bytes.len() + 1);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.shortest_path(mid).is_some() {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        self.bytes.get(hi - 1).copied()
+    }
+
+    fn reverse(&self) -> Option<Coord> {
+        let (start, exit) = (self.start(), self.exit());
+        let mut fallen = GridMap::new(self.size, self.size);
+        for &coord in &self.bytes {
+            let count = fallen.get(&coord).map_or(1, |count| count + 1);
+            fallen.insert(coord, count);
         }
 
-        for neighbor in pos.neighbors().filter(|coord| !corruptions.contains(coord)) {
-            let ncost = cost + 1;
-            let stored_ncost = *ledger.get(&neighbor).unwrap_or(&usize::MAX);
-            if ncost < stored_ncost {
-                ledger.insert(neighbor, ncost);
-                queue.push(State {
-                    cost: ncost,
-                    pos: neighbor,
-                });
+        let mut cells = OpenCells::new(self.size, self.size);
+        for y in 0..self.size as isize {
+            for x in 0..self.size as isize {
+                let coord = Coord::new(x, y);
+                if !fallen.contains_key(&coord) {
+                    cells.open(coord);
+                }
             }
         }
+        if cells.connected(start, exit) {
+            return None;
+        }
+
+        // takes the bytes away again, latest first, until the exit becomes reachable.
+        for &coord in self.bytes.iter().rev() {
+            let count = fallen.get_mut(&coord).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                cells.open(coord);
+            }
+            if cells.connected(start, exit) {
+                return Some(coord);
+            }
+        }
+        None
     }
-    None
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let corruptions: Vec<_> = input.lines().map(Coord::from).collect();
-    shortest_path(&corruptions[..1024])
+fn format_byte(coord: Coord) -> String {
+    format!("{},{}", coord.x, coord.y)
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let memory = Memory::parse(input, SIZE)?;
+    let text = Input::new(input);
+    let last = input.lines().nth(FALLEN - 1).ok_or_else(|| {
+        text.error(
+            &input[input.len()..],
+            format!("expected at least {FALLEN} bytes"),
+        )
+    })?;
+    memory
+        .shortest_path(FALLEN)
+        .ok_or_else(|| text.error(last, format!("the exit is cut off after {FALLEN} bytes")))
+}
+
+pub fn part_two(input: &str) -> Result<String, ParseError> {
+    let memory = Memory::parse(input, SIZE)?;
+    let text = Input::new(input);
+    memory
+        .first_blocking_byte(Method::Reverse)
+        .map(format_byte)
+        .ok_or_else(|| text.error(&input[input.len()..], "no byte cuts off the exit"))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Solves part two with the method following `--method`.
+fn solve_with(input: &str) {
+    let name = arg_value("--method").unwrap_or_default();
+    let Some(method) = Method::parse(&name) else {
+        let names: Vec<_> = Method::ALL.into_iter().map(Method::name).collect();
+        return eprintln!(
+            "unknown method `{name}`, expected one of {}",
+            names.join(", ")
+        );
+    };
+    let memory = match Memory::parse(input, SIZE) {
+        Ok(memory) => memory,
+        Err(err) => return eprintln!("{err}"),
+    };
+    let timer = Instant::now();
+    let byte = memory.first_blocking_byte(method);
+    println!(
+        "{}: {} ({:?})",
+        method.name(),
+        byte.map_or("none".to_string(), format_byte),
+        timer.elapsed()
+    );
+}
+
+/// Times every method of part two.
+fn compare(input: &str) {
+    let memory = match Memory::parse(input, SIZE) {
+        Ok(memory) => memory,
+        Err(err) => return eprintln!("{err}"),
+    };
+    for method in Method::ALL {
+        let timer = Instant::now();
+        let byte = memory.first_blocking_byte(method);
+        println!(
+            "{:<8} {} ({:?})",
+            method.name(),
+            byte.map_or("none".to_string(), format_byte),
+            timer.elapsed()
+        );
+    }
 }
 
-pub fn part_two(input: &str) -> Option<String> {
-    let corruptions: Vec<_> = input.lines().map(Coord::from).collect();
-    (0..corruptions.len())
-        .into_par_iter()
-        .find_first(|&i| shortest_path(&corruptions[..=i]).is_none())
-        .map(|i| corruptions[i])
-        .map(|Coord(x, y)| format!("{},{}", x, y))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Memory {
+        Memory::parse(&advent_of_code::template::read_file("examples", DAY), 7).unwrap()
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        assert_eq!(example().shortest_path(12), Some(22));
+    }
+
+    #[test]
+    fn test_first_blocking_byte() {
+        let memory = example();
+        for method in Method::ALL {
+            assert_eq!(
+                memory.first_blocking_byte(method),
+                Some(Coord::new(6, 1)),
+                "{method:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_never_blocked() {
+        let memory = Memory {
+            size: 7,
+            bytes: example().bytes[..12].to_vec(),
+        };
+        for method in Method::ALL {
+            assert_eq!(memory.first_blocking_byte(method), None, "{method:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Memory::parse("1,2\n3", 7).err().unwrap();
+        assert_eq!((err.line, err.message.as_str()), (2, "expected \",\""));
+        let err = Memory::parse("1,2\n7,0", 7).err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(part_one("1,2").is_err());
+    }
 }
@@ -1,61 +1,70 @@
-use std::collections::HashMap;
+use advent_of_code::{
+    memo::Memo,
+    parse::{Input, ParseError},
+};
 
 advent_of_code::solution!(19);
 
-struct Input {
+struct Onsen {
     towels: Vec<String>,
     designs: Vec<String>,
 }
 
-impl From<&str> for Input {
-    fn from(value: &str) -> Self {
-        let (towels_str, designs_str) = value.split_once("\n\n").unwrap();
-        let towels = towels_str.split(", ").map(str::to_string).collect();
+impl Onsen {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let mut sections = input.sections();
+        let (towels_str, designs_str) = match (sections.next(), sections.next()) {
+            (Some(towels), Some(designs)) => (towels, designs),
+            _ => return Err(input.error(input.as_str(), "expected towels and designs")),
+        };
+        // an empty towel would match forever without using up any of a design.
+        let towels = towels_str
+            .split(", ")
+            .map(|towel| match towel.trim() {
+                "" => Err(input.error(towel, "expected a towel")),
+                towel => Ok(towel.to_string()),
+            })
+            .collect::<Result<_, _>>()?;
         let designs = designs_str.lines().map(str::to_string).collect();
-        Self { towels, designs }
+        Ok(Self { towels, designs })
     }
 }
 
-fn valid_designs<'m, 's: 'm>(
-    memo: &'m mut HashMap<&'s str, usize>,
-    towels: &[String],
-    design: &'s str,
-) -> usize {
+fn valid_designs<'s>(memo: &mut Memo<&'s str, usize>, towels: &[String], design: &'s str) -> usize {
     if design.is_empty() {
         return 1;
     }
 
-    if let Some(ans) = memo.get(design) {
-        return *ans;
-    }
-
-    let ans = towels
-        .iter()
-        .filter_map(|towel| design.strip_prefix(towel))
-        .map(|design2| valid_designs(memo, towels, design2))
-        .sum();
-    memo.insert(design, ans);
-    ans
+    memo.get_or_insert_with(design, |memo| {
+        towels
+            .iter()
+            .filter_map(|towel| design.strip_prefix(towel))
+            .map(|rest| valid_designs(memo, towels, rest))
+            .sum()
+    })
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let Input { towels, designs } = input.into();
-    let mut memo = HashMap::new();
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let Onsen { towels, designs } = Onsen::parse(input)?;
+    let mut memo = Memo::new();
     let ans = designs
         .iter()
         .filter(|design| valid_designs(&mut memo, &towels, design) != 0)
         .count();
-    Some(ans)
+    memo.report("valid_designs");
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let Input { towels, designs } = input.into();
-    let mut memo = HashMap::new();
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let Onsen { towels, designs } = Onsen::parse(input)?;
+    let mut memo = Memo::new();
     let ans = designs
         .iter()
         .map(|design| valid_designs(&mut memo, &towels, design))
         .sum();
-    Some(ans)
+    memo.report("valid_designs");
+    Ok(ans)
 }
 
 #[cfg(test)]
@@ -65,12 +74,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
+        assert_eq!(result, Ok(6));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(16));
+        assert_eq!(result, Ok(16));
     }
 }
@@ -1,50 +1,23 @@
-use std::collections::{HashSet, VecDeque};
-
-use ndarray::Array2;
+use advent_of_code::{
+    geom::Coord,
+    grid::Grid,
+    parse::{Input, ParseError},
+    search,
+};
 
 advent_of_code::solution!(20);
 
-type Coord = (usize, usize);
-
 struct Map {
-    tiles: Array2<char>,
+    tiles: Grid<char>,
+    start: Coord,
+    end: Coord,
 }
 
 impl Map {
-    fn neighbors(&self, coord: &Coord) -> impl Iterator<Item = Coord> {
-        let (height, width) = self.tiles.dim();
-        let (y, x) = *coord;
-        let mut v = Vec::new();
-        if y > 0 {
-            v.push((y - 1, x));
-        }
-        if y + 1 < height {
-            v.push((y + 1, x));
-        }
-        if x > 0 {
-            v.push((y, x - 1));
-        }
-        if x + 1 < width {
-            v.push((y, x + 1));
-        }
-        v.into_iter()
-    }
-
-    fn find_tile(&self, ch: char) -> Option<Coord> {
-        for (coord, tile) in self.tiles.indexed_iter() {
-            if *tile == ch {
-                return Some(coord);
-            }
-        }
-        None
-    }
-
-    fn start(&self) -> Coord {
-        self.find_tile('S').expect("Start tile must exist")
-    }
-
-    fn end(&self) -> Coord {
-        self.find_tile('E').expect("End tile must exist")
+    fn neighbors(&self, coord: &Coord) -> impl Iterator<Item = Coord> + '_ {
+        coord
+            .neighbors4()
+            .filter(|&pos| self.tiles.contains(pos) && !self.is_wall(&pos))
     }
 
     fn is_wall(&self, pos: &Coord) -> bool {
@@ -52,32 +25,18 @@ impl Map {
     }
 
     fn cheats(&self, cheat_duration: usize, cheat_threshold: usize) -> usize {
-        let start = self.start();
-        let end = self.end();
+        let (start, end) = (self.start, self.end);
 
-        let mut path = Vec::new();
-        let mut visited = HashSet::new();
-
-        let mut queue = VecDeque::new();
-        queue.push_back(start);
-        while let Some(pos) = queue.pop_front() {
-            path.push(pos);
-            visited.insert(pos);
-            if pos == end {
-                break;
-            }
-            for next in self.neighbors(&pos) {
-                if self.is_wall(&next) || visited.contains(&next) {
-                    continue;
-                }
-                queue.push_back(next);
-            }
-        }
+        // the race track is a single path, so ordering its tiles by distance walks it.
+        let search = search::bfs([start], |pos| self.neighbors(pos), |&pos| pos == end);
+        let mut path: Vec<_> = search.into_distances().into_iter().collect();
+        path.sort_unstable_by_key(|&(_, dist)| dist);
+        let path: Vec<_> = path.into_iter().map(|(pos, _)| pos).collect();
 
         let mut total_cheats = 0;
         for (i, begin) in path.iter().enumerate() {
             for (j, end) in path.iter().enumerate().skip(i) {
-                let cheat_cost = begin.0.abs_diff(end.0) + begin.1.abs_diff(end.1);
+                let cheat_cost = begin.manhattan(*end) as usize;
                 if cheat_cost > cheat_duration {
                     continue;
                 }
@@ -95,28 +54,35 @@ impl Map {
     }
 }
 
-impl From<&str> for Map {
-    fn from(value: &str) -> Self {
-        let chars: Vec<_> = value.chars().filter(|c| *c != '\n').collect();
-        let height = value.lines().count();
-        let width = chars.len() / height;
-        let shape = (height, width);
-        let tiles = Array2::from_shape_vec(shape, chars).unwrap();
-        Self { tiles }
+impl Map {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+        let tiles = Grid::try_parse(&input, input.as_str(), |c| c)?;
+        let find = |tile: char, name: &str| {
+            tiles.find(&tile).ok_or_else(|| {
+                input.error(input.as_str(), format!("expected the {name} tile `{tile}`"))
+            })
+        };
+        let (start, end) = (find('S', "start")?, find('E', "end")?);
+        Ok(Self { tiles, start, end })
     }
 }
 
-fn get_cheats(input: &str, cheat_duration: usize, cheat_threshold: usize) -> usize {
-    let map: Map = input.into();
-    map.cheats(cheat_duration, cheat_threshold)
+fn get_cheats(
+    input: &str,
+    cheat_duration: usize,
+    cheat_threshold: usize,
+) -> Result<usize, ParseError> {
+    let map = Map::parse(input)?;
+    Ok(map.cheats(cheat_duration, cheat_threshold))
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    Some(get_cheats(input, 2, 100))
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    get_cheats(input, 2, 100)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    Some(get_cheats(input, 20, 100))
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    get_cheats(input, 20, 100)
 }
 
 #[cfg(test)]
@@ -126,7 +92,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = get_cheats(&advent_of_code::template::read_file("examples", DAY), 2, 0);
-        assert_eq!(result, 44);
+        assert_eq!(result, Ok(44));
     }
 
     #[test]
@@ -136,6 +102,6 @@ mod tests {
             20,
             50,
         );
-        assert_eq!(result, 285);
+        assert_eq!(result, Ok(285));
     }
 }
@@ -1,188 +1,239 @@
-use std::{cmp::Ordering, collections::HashMap};
+use advent_of_code::{
+    geom::{Coord, Dir4},
+    parse::{Input, ParseError},
+    search,
+};
+use std::collections::HashMap;
 
 advent_of_code::solution!(21);
 
-fn numpad_coord(button: char) -> (usize, usize) {
-    match button {
-        '7' => (0, 0),
-        '8' => (1, 0),
-        '9' => (2, 0),
-        '4' => (0, 1),
-        '5' => (1, 1),
-        '6' => (2, 1),
-        '1' => (0, 2),
-        '2' => (1, 2),
-        '3' => (2, 2),
-        '0' => (1, 3),
-        'A' => (2, 3),
-        _ => unreachable!("Invalid numpad button: {}", button),
-    }
-}
+/// The door's keypad, rows from top to bottom with a space for the gap.
+const NUMERIC: &str = "789\n456\n123\n 0A";
+/// The keypad the robots are operated with.
+const DIRECTIONAL: &str = " ^A\n<v>";
 
-fn press_numpad(input: &str) -> String {
-    let mut sequence = String::new();
-    let mut current = numpad_coord('A');
-    for button in input.chars().map(numpad_coord) {
-        if current != button {
-            let (sx, sy) = current;
-            let (dx, dy) = button;
-
-            let nx = sx.abs_diff(dx);
-            let ny = sy.abs_diff(dy);
-
-            let mut horizontal = match sx.cmp(&dx) {
-                Ordering::Equal => vec![],
-                Ordering::Less => vec!['>'; nx],
-                Ordering::Greater => vec!['<'; nx],
-            };
-
-            let mut vertical = match sy.cmp(&dy) {
-                Ordering::Equal => vec![],
-                Ordering::Less => vec!['v'; ny],
-                Ordering::Greater => vec!['^'; ny],
-            };
-
-            let mut presses = Vec::new();
-            match (sx.cmp(&dx), sy.cmp(&dy)) {
-                (Ordering::Equal, Ordering::Equal) => {}
-                (_, Ordering::Equal) => presses.append(&mut horizontal),
-                (Ordering::Equal, _) => presses.append(&mut vertical),
-                (Ordering::Greater, Ordering::Greater) => {
-                    if sy == 3 && dx == 0 {
-                        presses.append(&mut vertical);
-                        presses.append(&mut horizontal);
-                    } else {
-                        presses.append(&mut horizontal);
-                        presses.append(&mut vertical);
-                    }
-                }
-                (Ordering::Less, Ordering::Less) => {
-                    if sx == 0 && dy == 3 {
-                        presses.append(&mut horizontal);
-                        presses.append(&mut vertical);
-                    } else {
-                        presses.append(&mut vertical);
-                        presses.append(&mut horizontal);
-                    }
-                }
-                (Ordering::Greater, Ordering::Less) => {
-                    presses.append(&mut vertical);
-                    presses.append(&mut horizontal);
-                }
-                (Ordering::Less, Ordering::Greater) => {
-                    presses.append(&mut vertical);
-                    presses.append(&mut horizontal);
-                }
-            }
-            sequence.extend(presses.into_iter());
-        }
-        sequence.push('A');
-        current = button;
-    }
-    sequence
-}
+/// Presses by the human for moving the arm over a keypad from one key to another and pressing
+/// it, by pair of keys.
+type Costs = HashMap<(char, char), usize>;
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
-struct State {
-    input: String,
-    depth: usize,
-}
+/// The keys of a directional keypad moving the arm pointing at the next keypad.
+const MOVES: [char; 4] = ['^', '>', 'v', '<'];
 
-type Cache = HashMap<State, usize>;
+struct Keypad {
+    keys: HashMap<char, Coord>,
+    positions: HashMap<Coord, char>,
+}
 
-fn press_keypad(input: &str, depth: usize, cache: &mut Cache) -> usize {
-    if depth == 0 {
-        return input.len();
+impl Keypad {
+    /// Parses one row per line, where a space is a gap the arm must never point at. The arm
+    /// starts on `A`.
+    fn new(layout: &str) -> Self {
+        let keys: HashMap<_, _> = layout
+            .lines()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, key)| key != ' ')
+                    .map(move |(x, key)| (key, Coord::from_index((y, x))))
+            })
+            .collect();
+        assert!(keys.contains_key(&'A'), "a keypad needs an `A` key");
+        let positions = keys.iter().map(|(&key, &pos)| (pos, key)).collect();
+        Self { keys, positions }
     }
 
-    let state = State {
-        input: input.to_string(),
-        depth,
-    };
-
-    if let Some(presses) = cache.get(&state) {
-        return *presses;
+    /// The costs when the human presses the keys directly.
+    fn direct_costs(&self) -> Costs {
+        let keys = || self.keys.keys().copied();
+        keys()
+            .flat_map(|from| keys().map(move |to| ((from, to), 1)))
+            .collect()
     }
 
-    let mut total_presses = 0;
-    let mut current = 'A';
-    for button in input.chars() {
-        let keys = match (current, button) {
-            ('A', 'A') => "A",
-            ('A', '^') => "<A",
-            ('A', '>') => "vA",
-            ('A', 'v') => "<vA",
-            ('A', '<') => "v<<A",
-
-            ('^', 'A') => ">A",
-            ('^', '^') => "A",
-            ('^', '>') => "v>A",
-            ('^', 'v') => "vA",
-            ('^', '<') => "v<A",
-
-            ('>', 'A') => "^A",
-            ('>', '^') => "<^A",
-            ('>', '>') => "A",
-            ('>', 'v') => "<A",
-            ('>', '<') => "<<A",
-
-            ('v', 'A') => "^>A",
-            ('v', '^') => "^A",
-            ('v', '>') => ">A",
-            ('v', 'v') => "A",
-            ('v', '<') => "<A",
-
-            ('<', 'A') => ">>^A",
-            ('<', '^') => ">^A",
-            ('<', '>') => ">>A",
-            ('<', 'v') => ">A",
-            ('<', '<') => "A",
-            _ => unreachable!("Invalid combination"),
+    /// The costs when a robot moves the arm, operated through a directional keypad with the
+    /// costs `outer`.
+    ///
+    /// The robot's arm on the directional keypad starts and ends on `A`, in between every move
+    /// here is a press there. The cheapest way from one key to another is found by a search over
+    /// the position here and the last key pressed there.
+    fn costs(&self, outer: &Costs) -> Costs {
+        let outer_cost = |from: char, to: char| {
+            *outer
+                .get(&(from, to))
+                .unwrap_or_else(|| panic!("the outer keypad has no keys `{from}` and `{to}`"))
         };
 
-        total_presses += press_keypad(keys, depth - 1, cache);
-        current = button;
+        let mut costs = Costs::new();
+        for (&from, &start) in &self.keys {
+            let search = search::dijkstra(
+                [(start, 'A')],
+                |&(pos, last)| {
+                    MOVES.into_iter().filter_map(move |key| {
+                        let next = pos.step(Dir4::try_from(key).unwrap());
+                        self.positions
+                            .contains_key(&next)
+                            .then(|| ((next, key), outer_cost(last, key)))
+                    })
+                },
+                |_| false,
+            );
+            for (&to, &end) in &self.keys {
+                let cost = ['A'].into_iter().chain(MOVES).filter_map(|last| {
+                    Some(search.distance(&(end, last))? + outer_cost(last, 'A'))
+                });
+                costs.insert((from, to), cost.min().expect("every key can be reached"));
+            }
+        }
+        costs
     }
+}
 
-    cache.insert(state, total_presses);
-    total_presses
+/// The costs on the last keypad of `chain`. The human presses the keys of the first one, and a
+/// robot operated with each keypad presses the keys of the next.
+fn chain_costs(chain: &[&Keypad]) -> Costs {
+    let (first, rest) = chain.split_first().expect("the chain is empty");
+    rest.iter()
+        .fold(first.direct_costs(), |costs, keypad| keypad.costs(&costs))
 }
 
-fn sequence_len(input: &str, chain: usize) -> usize {
-    let sequence = press_numpad(input);
-    let mut cache = Cache::new();
-    press_keypad(&sequence, chain, &mut cache)
+/// Presses by the human to type `code`, starting on `A`.
+fn sequence_len(code: &str, costs: &Costs) -> usize {
+    let from = ['A'].into_iter().chain(code.chars());
+    from.zip(code.chars()).map(|pair| costs[&pair]).sum()
 }
 
-fn numeric_value(input: &str) -> usize {
-    input.strip_suffix("A").unwrap().parse().unwrap()
+/// The codes to type, digits followed by `A`, with their numeric values.
+fn codes(input: &str) -> Result<Vec<(&str, usize)>, ParseError> {
+    let input = Input::new(input);
+    input
+        .as_str()
+        .lines()
+        .map(|code| {
+            let digits = code
+                .strip_suffix('A')
+                .ok_or_else(|| input.error(code, "expected a code ending in `A`"))?;
+            if let Some(i) = digits.find(|c: char| !c.is_ascii_digit()) {
+                return Err(input.error(&digits[i..], "expected a digit"));
+            }
+            Ok((code, input.parse(digits)?))
+        })
+        .collect()
 }
 
-fn calculate(input: &str, chain: usize) -> usize {
-    sequence_len(input, chain) * numeric_value(input)
+/// The sum of complexities with `robots` robots on directional keypads between the human and the
+/// robot at the door.
+fn calculate(input: &str, robots: usize) -> Result<usize, ParseError> {
+    let codes = codes(input)?;
+    let (numeric, directional) = (Keypad::new(NUMERIC), Keypad::new(DIRECTIONAL));
+    let mut chain = vec![&directional; robots + 1];
+    chain.push(&numeric);
+    let costs = chain_costs(&chain);
+    Ok(codes
+        .into_iter()
+        .map(|(code, value)| sequence_len(code, &costs) * value)
+        .sum())
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    Some(input.lines().map(|line| calculate(line, 2)).sum())
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    calculate(input, 2)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    Some(input.lines().map(|line| calculate(line, 25)).sum())
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    calculate(input, 25)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::{HashSet, VecDeque};
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(126384));
+        assert_eq!(result, Ok(126384));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(154115708116294));
+        assert_eq!(result, Ok(154115708116294));
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        let err = part_one("029A\n98B0A").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(
+            part_one("029").unwrap_err().message,
+            "expected a code ending in `A`"
+        );
+    }
+
+    /// Presses to type `code` on the last keypad, by a search over the arms of every robot.
+    fn brute_force(chain: &[&Keypad], code: &str) -> usize {
+        let code: Vec<_> = code.chars().collect();
+        let arms: Vec<_> = chain[1..].iter().map(|keypad| keypad.keys[&'A']).collect();
+        let mut seen = HashSet::from([(arms.clone(), 0)]);
+        let mut queue = VecDeque::from([(arms, 0, 0)]);
+        while let Some((arms, typed, presses)) = queue.pop_front() {
+            if typed == code.len() {
+                return presses;
+            }
+            'press: for &pressed in chain[0].keys.keys() {
+                let (mut arms, mut typed, mut key) = (arms.clone(), typed, pressed);
+                for (level, keypad) in chain[1..].iter().enumerate() {
+                    if key != 'A' {
+                        arms[level] = arms[level].step(Dir4::try_from(key).unwrap());
+                        if !keypad.positions.contains_key(&arms[level]) {
+                            continue 'press;
+                        }
+                        break;
+                    }
+                    key = keypad.positions[&arms[level]];
+                    if level == chain.len() - 2 {
+                        if key != code[typed] {
+                            continue 'press;
+                        }
+                        typed += 1;
+                    }
+                }
+                if seen.insert((arms.clone(), typed)) {
+                    queue.push_back((arms, typed, presses + 1));
+                }
+            }
+        }
+        unreachable!("{code:?} cannot be typed")
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let (numeric, directional) = (Keypad::new(NUMERIC), Keypad::new(DIRECTIONAL));
+        // a keypad with two gaps, where the direct way between some keys is blocked.
+        let custom = Keypad::new("1 2\n3A4\n 56");
+        let codes = ["029A", "980A", "179A", "456A", "379A"];
+        for robots in 0..3 {
+            let mut chain = vec![&directional; robots + 1];
+            chain.push(&numeric);
+            let costs = chain_costs(&chain);
+            for code in codes {
+                assert_eq!(
+                    sequence_len(code, &costs),
+                    brute_force(&chain, code),
+                    "{code} through {robots} robots"
+                );
+            }
+
+            chain.pop();
+            chain.push(&custom);
+            let costs = chain_costs(&chain);
+            for code in ["152A", "6A1", "3645"] {
+                assert_eq!(
+                    sequence_len(code, &costs),
+                    brute_force(&chain, code),
+                    "{code} through {robots} robots"
+                );
+            }
+        }
     }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use advent_of_code::parse::{Input, ParseError};
+
 advent_of_code::solution!(22);
 
 struct SecretNumberIter {
@@ -21,17 +23,25 @@ fn generate(number: usize) -> SecretNumberIter {
     SecretNumberIter { number }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let ans = input
+fn numbers(input: &str) -> Result<Vec<usize>, ParseError> {
+    let input = Input::new(input);
+    input
+        .as_str()
         .lines()
-        .map(|line| line.parse().unwrap())
+        .map(|line| input.parse(line))
+        .collect()
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let ans = numbers(input)?
+        .into_iter()
         .filter_map(|n| generate(n).take(2000).last())
         .sum();
-    Some(ans)
+    Ok(ans)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let numbers: Vec<_> = input.lines().map(|line| line.parse().unwrap()).collect();
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let numbers = numbers(input)?;
 
     let mut tally = HashMap::new();
     for number in numbers {
@@ -54,7 +64,8 @@ pub fn part_two(input: &str) -> Option<usize> {
         }
     }
 
-    tally.values().max().map(|&n| n as usize)
+    // without any buyers there are no bananas to be had.
+    Ok(tally.values().max().map_or(0, |&n| n as usize))
 }
 
 #[cfg(test)]
@@ -66,7 +77,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(37327623));
+        assert_eq!(result, Ok(37327623));
     }
 
     #[test]
@@ -74,6 +85,6 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(23));
+        assert_eq!(result, Ok(23));
     }
 }
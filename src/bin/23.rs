@@ -1,85 +1,50 @@
-use std::collections::{HashMap, HashSet};
-
+use advent_of_code::{
+    graph::{Graph, NodeId},
+    parse::{Input, ParseError},
+};
 use rayon::prelude::*;
 
 advent_of_code::solution!(23);
 
-struct Graph<'i> {
-    nodes: HashMap<&'i str, HashSet<&'i str>>,
-}
-
-impl<'i> Graph<'i> {
-    fn trios(&'i self) -> HashSet<[&'i str; 3]> {
-        let connections = &self.nodes;
-        let mut list = HashSet::new();
-        for (&first, conns0) in &self.nodes {
-            for &second in conns0 {
-                let conns1 = &connections[second];
-                for &third in conns0.intersection(conns1) {
-                    let mut idx = [first, second, third];
-                    idx.sort();
-                    list.insert(idx);
-                }
+/// The largest group of nodes that are all connected to each other, if any node has a
+/// connection.
+fn largest(graph: &Graph<&str>) -> Option<Vec<NodeId>> {
+    graph
+        .triangles()
+        .into_par_iter()
+        .map(|trio| {
+            // grow the group greedily with nodes connected to every member so far.
+            let mut group = trio.to_vec();
+            let mut candidates = graph.neighbors(trio[0]) & graph.neighbors(trio[1]);
+            candidates.intersect_with(graph.neighbors(trio[2]));
+            while let Some(candidate) = candidates.first() {
+                group.push(candidate);
+                candidates.intersect_with(graph.neighbors(candidate));
             }
-        }
-        list
-    }
-
-    fn largest(&'i self) -> Vec<&'i str> {
-        let connections = &self.nodes;
-        self.trios()
-            .into_par_iter()
-            .map(|trio| {
-                let mut group: HashSet<_> = HashSet::from_iter(trio);
-                for candidate in self.nodes.keys() {
-                    if group.contains(candidate) {
-                        continue;
-                    }
-                    if group
-                        .iter()
-                        .all(|node| connections[node].contains(candidate))
-                    {
-                        group.insert(candidate);
-                    }
-                }
-                group
-            })
-            .max_by(|lhs, rhs| lhs.len().cmp(&rhs.len()))
-            .map(|group| {
-                let mut group = Vec::from_iter(group);
-                group.sort();
-                group
-            })
-            .unwrap()
-    }
+            group
+        })
+        .max_by_key(|group| group.len())
+        // without any triangle, every connection is a largest group.
+        .or_else(|| graph.edges().next().map(|(a, b)| vec![a, b]))
 }
 
-impl<'i> From<&'i str> for Graph<'i> {
-    fn from(value: &'i str) -> Self {
-        let mut nodes: HashMap<_, HashSet<_>> = HashMap::new();
-        for line in value.lines() {
-            let (lhs, rhs) = line.split_once("-").unwrap();
-            nodes.entry(lhs).or_default().insert(rhs);
-            nodes.entry(rhs).or_default().insert(lhs);
-        }
-        Self { nodes }
-    }
-}
-
-pub fn part_one(input: &str) -> Option<usize> {
-    let graph: Graph = input.into();
-    Some(
-        graph
-            .trios()
-            .into_iter()
-            .filter(|idx| idx.iter().any(|s| s.starts_with("t")))
-            .count(),
-    )
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let graph = Graph::parse_undirected(input, "-")?;
+    Ok(graph
+        .triangles()
+        .into_iter()
+        .filter(|trio| trio.iter().any(|&id| graph.label(id).starts_with('t')))
+        .count())
 }
 
-pub fn part_two(input: &str) -> Option<String> {
-    let graph: Graph = input.into();
-    Some(graph.largest().join(","))
+pub fn part_two(input: &str) -> Result<String, ParseError> {
+    let graph = Graph::parse_undirected(input, "-")?;
+    let Some(group) = largest(&graph) else {
+        return Err(Input::new(input).error(input, "expected a connection"));
+    };
+    let mut group: Vec<_> = group.into_iter().map(|id| *graph.label(id)).collect();
+    group.sort_unstable();
+    Ok(group.join(","))
 }
 
 #[cfg(test)]
@@ -89,12 +54,18 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(7));
+        assert_eq!(result, Ok(7));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some("co,de,ka,ta".to_string()));
+        assert_eq!(result, Ok("co,de,ka,ta".to_string()));
+    }
+
+    #[test]
+    fn test_part_two_without_triangles() {
+        assert_eq!(part_two("ab-cd"), Ok("ab,cd".to_string()));
+        assert!(part_two("").is_err());
     }
 }
@@ -0,0 +1,217 @@
+/// Cycle detection for sequences `x0, f(x0), f(f(x0)), ...` over a finite state space.
+///
+/// Such a sequence runs through `start` (μ) states before entering a loop of `length` (λ)
+/// states. [`floyd`] and [`brent`] find that [`Cycle`] in constant memory by comparing states,
+/// [`hashed`] remembers every state and finds it with the fewest calls to the step function.
+/// Once the cycle is known, the state after any number of steps is reached without walking all
+/// of them, see [`nth`] and [`History::nth`].
+///
+/// All of them loop forever if the sequence never repeats. A sequence that can end, e.g. a walker
+/// leaving the map, can be made endless with `Option` states where `None` steps to `None`.
+use std::{collections::HashMap, hash::Hash};
+
+/// Where a sequence starts repeating: `x[start] == x[start + length]`, for the smallest such
+/// `start` and `length`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The index in `0..start + length` of the state equal to the one after `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare.
+pub fn floyd<S: Clone + Eq>(x0: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&x0);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // the hare is a multiple of the cycle length ahead, so both meet at its start.
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, which needs fewer steps than [`floyd`] on average.
+pub fn brent<S: Clone + Eq>(x0: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let length = cycle_length(x0.clone(), &mut step);
+
+    // with the hare `length` steps ahead, both meet at the start of the cycle.
+    let mut start = 0;
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Only the length of the cycle, the first half of [`brent`].
+pub fn cycle_length<S: Clone + Eq>(x0: S, mut step: impl FnMut(&S) -> S) -> usize {
+    // the tortoise waits at powers of two until the hare comes around.
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    length
+}
+
+/// The state after `n` steps, found by skipping whole laps once [`brent`] found the cycle.
+pub fn nth<S: Clone + Eq>(x0: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = brent(x0.clone(), &mut step);
+    let mut state = x0;
+    for _ in 0..cycle.reduce(n) {
+        state = step(&state);
+    }
+    state
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Every state of a sequence up to the point where it repeats, see [`hashed`].
+#[derive(Clone, Debug)]
+pub struct History<S> {
+    states: Vec<S>,
+    cycle: Cycle,
+}
+
+impl<S> History<S> {
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    /// The states `x0` to `x[start + length - 1]`, each of them distinct.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The state after `n` steps.
+    pub fn nth(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+/// Records states until one repeats. Calls `step` only `start + length` times, but keeps every
+/// state in memory.
+pub fn hashed<S: Clone + Eq + Hash>(x0: S, mut step: impl FnMut(&S) -> S) -> History<S> {
+    let mut states = Vec::new();
+    let mut seen = HashMap::new();
+    let mut state = x0;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let length = states.len() - start;
+            return History {
+                states,
+                cycle: Cycle { start, length },
+            };
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, cycle_length, floyd, hashed, nth, Cycle};
+
+    /// `0, 1, ..., 6, 3, 4, 5, 6, 3, ...`
+    fn rho(n: &u32) -> u32 {
+        if *n == 6 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let expected = Cycle {
+            start: 3,
+            length: 4,
+        };
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+        assert_eq!(hashed(0, rho).cycle(), expected);
+
+        let fixed_point = Cycle {
+            start: 0,
+            length: 1,
+        };
+        assert_eq!(floyd(5, |&n| n), fixed_point);
+        assert_eq!(brent(5, |&n| n), fixed_point);
+        assert_eq!(hashed(5, |&n| n).cycle(), fixed_point);
+    }
+
+    #[test]
+    fn agrees_on_random_maps() {
+        // x -> x^2 + c mod m has tails and cycles of all sorts of lengths.
+        for m in 1..60_u64 {
+            for c in 0..m {
+                let step = |x: &u64| (x * x + c) % m;
+                let history = hashed(0, step);
+                assert_eq!(floyd(0, step), history.cycle(), "m = {m}, c = {c}");
+                assert_eq!(brent(0, step), history.cycle(), "m = {m}, c = {c}");
+                assert_eq!(cycle_length(0, step), history.cycle().length);
+            }
+        }
+    }
+
+    #[test]
+    fn skips_ahead() {
+        let history = hashed(0, rho);
+        assert_eq!(history.states(), &[0, 1, 2, 3, 4, 5, 6]);
+        for n in 0..50 {
+            let mut expected = 0;
+            for _ in 0..n {
+                expected = rho(&expected);
+            }
+            assert_eq!(*history.nth(n), expected, "{n}");
+            assert_eq!(nth(0, rho, n), expected, "{n}");
+        }
+        assert_eq!(nth(0, rho, 1_000_000_000_000), 4);
+    }
+}
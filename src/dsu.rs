@@ -0,0 +1,205 @@
+/// Union-find over dense ids, and connectivity of open cells on a grid built on top of it.
+///
+/// [`Dsu`] merges sets in near-constant time with path compression and union by size. It can
+/// only merge, never split. [`OpenCells`] links each cell it opens to its open neighbors, which
+/// answers "are these two cells connected yet?" as cells are added. Removals are handled
+/// offline: start from the final grid and add the removed cells back in reverse order.
+use crate::geom::Coord;
+
+#[derive(Clone, Debug)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl Dsu {
+    /// `len` singleton sets `0..len`.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// The representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            (x, self.parent[x]) = (self.parent[x], root);
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`. Returns whether they were separate.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The 4-connected components of the open cells of a `width` x `height` grid, as cells are
+/// opened one by one.
+#[derive(Clone, Debug)]
+pub struct OpenCells {
+    width: usize,
+    height: usize,
+    open: Vec<bool>,
+    dsu: Dsu,
+}
+
+impl OpenCells {
+    /// A grid where every cell is closed.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            open: vec![false; width * height],
+            dsu: Dsu::new(width * height),
+        }
+    }
+
+    fn id(&self, coord: Coord) -> Option<usize> {
+        let (row, col) = coord.checked_index()?;
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+
+    /// Opens `coord` and joins it with its open neighbors. Returns whether it was closed.
+    ///
+    /// # Panics
+    /// Panics if `coord` is outside of the grid.
+    pub fn open(&mut self, coord: Coord) -> bool {
+        let id = self.id(coord).unwrap_or_else(|| {
+            panic!(
+                "{coord} is outside of the {}x{} grid",
+                self.width, self.height
+            )
+        });
+        if self.open[id] {
+            return false;
+        }
+        self.open[id] = true;
+        for neighbor in coord.neighbors4() {
+            if let Some(other) = self.id(neighbor).filter(|&other| self.open[other]) {
+                self.dsu.union(id, other);
+            }
+        }
+        true
+    }
+
+    pub fn is_open(&self, coord: Coord) -> bool {
+        self.id(coord).is_some_and(|id| self.open[id])
+    }
+
+    /// Whether both cells are open and a path of open cells joins them.
+    pub fn connected(&mut self, a: Coord, b: Coord) -> bool {
+        match (self.id(a), self.id(b)) {
+            (Some(a), Some(b)) => self.open[a] && self.open[b] && self.dsu.same(a, b),
+            _ => false,
+        }
+    }
+
+    /// Number of open cells connected to `coord`, including itself.
+    pub fn component_size(&mut self, coord: Coord) -> usize {
+        match self.id(coord) {
+            Some(id) if self.open[id] => self.dsu.size_of(id),
+            _ => 0,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Dsu, OpenCells};
+    use crate::geom::Coord;
+
+    #[test]
+    fn merges_sets() {
+        let mut dsu = Dsu::new(6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 4));
+        assert_eq!(dsu.size_of(2), 4);
+        assert_eq!(dsu.size_of(5), 1);
+        assert_eq!(dsu.set_count(), 3);
+        assert_eq!(dsu.len(), 6);
+    }
+
+    #[test]
+    fn compresses_long_chains() {
+        let mut dsu = Dsu::new(100_000);
+        for i in 1..dsu.len() {
+            dsu.union(i - 1, i);
+        }
+        let root = dsu.find(0);
+        assert!((0..dsu.len()).all(|i| dsu.find(i) == root));
+        assert_eq!(dsu.set_count(), 1);
+    }
+
+    #[test]
+    fn connects_open_cells() {
+        // opens the middle column of a 3x3 grid last.
+        let mut cells = OpenCells::new(3, 3);
+        for y in 0..3 {
+            cells.open(Coord::new(0, y));
+            cells.open(Coord::new(2, y));
+        }
+        let (left, right) = (Coord::new(0, 0), Coord::new(2, 2));
+        assert!(!cells.connected(left, right));
+        assert_eq!(cells.component_size(left), 3);
+
+        assert!(cells.open(Coord::new(1, 1)));
+        assert!(!cells.open(Coord::new(1, 1)));
+        assert!(cells.connected(left, right));
+        assert_eq!(cells.component_size(right), 7);
+
+        assert!(!cells.is_open(Coord::new(1, 0)));
+        assert!(!cells.connected(left, Coord::new(1, 0)));
+        assert!(!cells.connected(left, Coord::new(-1, 0)));
+        assert_eq!(cells.component_size(Coord::new(1, 2)), 0);
+    }
+}
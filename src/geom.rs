@@ -0,0 +1,465 @@
+/// 2D points, vectors and directions on a grid where `x` grows to the right and `y` grows down.
+///
+/// Points convert to and from `(row, column)` ndarray indices, i.e. `(y, x)`. Converting a point
+/// with a negative component to an index is checked: [`Coord::checked_index`] returns `None`,
+/// [`Coord::index`] panics.
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// Signed integers that can be used as point components.
+pub trait Scalar:
+    Copy
+    + Debug
+    + Display
+    + Ord
+    + Hash
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn to_usize(self) -> Option<usize>;
+    fn from_usize(n: usize) -> Option<Self>;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+
+                fn from_usize(n: usize) -> Option<Self> {
+                    Self::try_from(n).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar!(i16, i32, i64, isize);
+
+/* -------------------------------------------------------------------------- */
+
+/// A position on the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord<T = isize> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A displacement between two positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T = isize> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Scalar> Coord<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// The position of an ndarray `(row, column)` index.
+    ///
+    /// # Panics
+    /// Panics if the index does not fit into `T`.
+    pub fn from_index((row, col): (usize, usize)) -> Self {
+        let convert =
+            |n| T::from_usize(n).unwrap_or_else(|| panic!("index {n} does not fit into a coord"));
+        Self::new(convert(col), convert(row))
+    }
+
+    /// The ndarray `(row, column)` index of this position, or `None` if a component is negative.
+    pub fn checked_index(self) -> Option<(usize, usize)> {
+        Some((self.y.to_usize()?, self.x.to_usize()?))
+    }
+
+    /// The ndarray `(row, column)` index of this position.
+    ///
+    /// # Panics
+    /// Panics if a component is negative.
+    pub fn index(self) -> (usize, usize) {
+        self.checked_index()
+            .unwrap_or_else(|| panic!("{self} has no index, it is left of or above the origin"))
+    }
+
+    pub fn step(self, dir: impl Into<Vec2<T>>) -> Self {
+        self + dir.into()
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        (other - self).manhattan()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        (other - self).chebyshev()
+    }
+
+    /// The four orthogonal neighbors, in the order of [`Dir4::ALL`].
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Dir4::ALL.into_iter().map(move |dir| self.step(dir))
+    }
+
+    /// The eight surrounding neighbors, in the order of [`Dir8::ALL`].
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        Dir8::ALL.into_iter().map(move |dir| self.step(dir))
+    }
+}
+
+impl<T: Scalar> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Rotates by 90° clockwise, as seen on screen.
+    pub fn turn_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates by 90° counterclockwise, as seen on screen.
+    pub fn turn_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    pub fn manhattan(self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev(self) -> T {
+        self.x.abs().max(self.y.abs())
+    }
+}
+
+impl<T: Display> Display for Coord<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Vec2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{},{}>", self.x, self.y)
+    }
+}
+
+impl<T: Scalar> Add<Vec2<T>> for Coord<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Vec2<T>) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Scalar> AddAssign<Vec2<T>> for Coord<T> {
+    fn add_assign(&mut self, rhs: Vec2<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Scalar> Sub<Vec2<T>> for Coord<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Vec2<T>) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Scalar> SubAssign<Vec2<T>> for Coord<T> {
+    fn sub_assign(&mut self, rhs: Vec2<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Scalar> Sub for Coord<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Scalar> Add for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Scalar> AddAssign for Vec2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Scalar> Sub for Vec2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Scalar> SubAssign for Vec2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Scalar> Mul<T> for Vec2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Scalar> Neg for Vec2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Scalar> Sum for Vec2<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The four orthogonal directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir4 {
+    /// All directions, clockwise starting north.
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn vector<T: Scalar>(self) -> Vec2<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        match self {
+            Self::North => Vec2::new(zero, -one),
+            Self::East => Vec2::new(one, zero),
+            Self::South => Vec2::new(zero, one),
+            Self::West => Vec2::new(-one, zero),
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::North | Self::South)
+    }
+}
+
+/// Parses the arrows `^>v<` used by most puzzles.
+impl TryFrom<char> for Dir4 {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '^' => Ok(Self::North),
+            '>' => Ok(Self::East),
+            'v' => Ok(Self::South),
+            '<' => Ok(Self::West),
+            c => Err(format!("\"{c}\" is not a direction")),
+        }
+    }
+}
+
+impl<T: Scalar> From<Dir4> for Vec2<T> {
+    fn from(value: Dir4) -> Self {
+        value.vector()
+    }
+}
+
+/// The eight directions including diagonals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    /// All directions, clockwise starting north.
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    /// Rotates by 45° clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotates by 45° counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn vector<T: Scalar>(self) -> Vec2<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        match self {
+            Self::North => Vec2::new(zero, -one),
+            Self::NorthEast => Vec2::new(one, -one),
+            Self::East => Vec2::new(one, zero),
+            Self::SouthEast => Vec2::new(one, one),
+            Self::South => Vec2::new(zero, one),
+            Self::SouthWest => Vec2::new(-one, one),
+            Self::West => Vec2::new(-one, zero),
+            Self::NorthWest => Vec2::new(-one, -one),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl<T: Scalar> From<Dir8> for Vec2<T> {
+    fn from(value: Dir8) -> Self {
+        value.vector()
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(value: Dir4) -> Self {
+        Self::ALL[value as usize * 2]
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Coord, Dir4, Dir8, Vec2};
+
+    #[test]
+    fn adds_vectors_to_coords() {
+        let a = Coord::new(1, 2);
+        let b = a + Vec2::new(3, -4);
+        assert_eq!(b, Coord::new(4, -2));
+        assert_eq!(b - a, Vec2::new(3, -4));
+        assert_eq!(a.step(Dir4::North), Coord::new(1, 1));
+        assert_eq!(Vec2::new(1, 2) * 3, Vec2::new(3, 6));
+    }
+
+    #[test]
+    fn measures_distances() {
+        let a = Coord::new(0, 0);
+        let b = Coord::new(-3, 4);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+    }
+
+    #[test]
+    fn turns_directions() {
+        for dir in Dir4::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_eq!(dir.vector::<i32>().turn_right(), dir.turn_right().vector());
+            assert_eq!(dir.vector::<i32>().turn_left(), dir.turn_left().vector());
+            assert_eq!(-dir.vector::<i32>(), dir.reverse().vector());
+        }
+        for dir in Dir8::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(-dir.vector::<i32>(), dir.reverse().vector());
+        }
+        assert_eq!(Dir8::from(Dir4::West), Dir8::West);
+        assert!(Dir8::SouthWest.is_diagonal());
+    }
+
+    #[test]
+    fn lists_neighbors() {
+        let origin: Coord = Coord::new(0, 0);
+        let neighbors: Vec<_> = origin.neighbors4().collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                Coord::new(0, -1),
+                Coord::new(1, 0),
+                Coord::new(0, 1),
+                Coord::new(-1, 0)
+            ]
+        );
+        assert_eq!(origin.neighbors8().count(), 8);
+        assert!(origin
+            .neighbors8()
+            .all(|n| origin.chebyshev(n) == 1 && n != origin));
+    }
+
+    #[test]
+    fn converts_indices() {
+        let coord: Coord = Coord::from_index((2, 5));
+        assert_eq!(coord, Coord::new(5, 2));
+        assert_eq!(coord.index(), (2, 5));
+        assert_eq!(Coord::new(-1, 0).checked_index(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_negative_index() {
+        Coord::new(0, -1).index();
+    }
+
+    #[test]
+    fn parses_arrows() {
+        let dirs: Vec<_> = "^>v<".chars().map(|c| Dir4::try_from(c).unwrap()).collect();
+        assert_eq!(dirs, Dir4::ALL);
+        assert!(Dir4::try_from('x').is_err());
+    }
+}
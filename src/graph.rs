@@ -0,0 +1,508 @@
+/// Graphs with labeled nodes, as given by puzzle inputs like `a-b` or `47|53`.
+///
+/// A [`Graph`] interns every label to a dense [`NodeId`] and stores adjacency as one [`BitSet`]
+/// per node, so neighborhoods can be intersected cheaply.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::{Display, Write},
+    hash::Hash,
+    ops::BitAnd,
+};
+
+use crate::parse::{Input, ParseError};
+
+pub type NodeId = usize;
+
+/// A growable set of small integers.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let is_new = self.words[word] & (1 << bit) == 0;
+        self.words[word] |= 1 << bit;
+        is_new
+    }
+
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        let Some(word) = self.words.get_mut(word) else {
+            return false;
+        };
+        let was_present = *word & (1 << bit) != 0;
+        *word &= !(1 << bit);
+        was_present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / 64)
+            .is_some_and(|word| word & (1 << (value % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    /// Values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (i, word) in self.words.iter_mut().enumerate() {
+            *word &= other.words.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+}
+
+impl BitAnd for &BitSet {
+    type Output = BitSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.intersect_with(rhs);
+        result
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    directed: bool,
+    labels: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    outgoing: Vec<BitSet>,
+    incoming: Vec<BitSet>,
+}
+
+/// The nodes of a cycle that prevents a topological sort, starting and ending with the same node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: Display> Display for Cycle<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph has a cycle: ")?;
+        for (i, node) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{node}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: std::fmt::Debug + Display> std::error::Error for Cycle<N> {}
+
+impl<'a> Graph<&'a str> {
+    /// Parses one edge per line, e.g. `a-b` with separator `"-"`, where `a` points to `b`.
+    pub fn parse_directed(input: &'a str, separator: &str) -> Result<Self, ParseError> {
+        let mut graph = Self::directed();
+        graph.extend_edges(parse_edges(input, separator)?);
+        Ok(graph)
+    }
+
+    /// Parses one edge per line, e.g. `a-b` with separator `"-"`, connecting `a` and `b`.
+    pub fn parse_undirected(input: &'a str, separator: &str) -> Result<Self, ParseError> {
+        let mut graph = Self::undirected();
+        graph.extend_edges(parse_edges(input, separator)?);
+        Ok(graph)
+    }
+}
+
+fn parse_edges<'a>(input: &'a str, separator: &str) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    let input = Input::new(input);
+    input
+        .as_str()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (from, to) = input.split_once(line, separator)?;
+            Ok((from.trim(), to.trim()))
+        })
+        .collect()
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the id of `label`, adding it as a node if it is new.
+    pub fn add_node(&mut self, label: N) -> NodeId {
+        if let Some(&id) = self.ids.get(&label) {
+            return id;
+        }
+        let id = self.labels.len();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        self.outgoing.push(BitSet::new());
+        self.incoming.push(BitSet::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) -> (NodeId, NodeId) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.outgoing[from].insert(to);
+        self.incoming[to].insert(from);
+        if !self.directed {
+            self.outgoing[to].insert(from);
+            self.incoming[from].insert(to);
+        }
+        (from, to)
+    }
+
+    pub fn extend_edges(&mut self, edges: impl IntoIterator<Item = (N, N)>) {
+        for (from, to) in edges {
+            self.add_edge(from, to);
+        }
+    }
+
+    pub fn id(&self, label: &N) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: NodeId) -> &N {
+        &self.labels[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> std::ops::Range<NodeId> {
+        0..self.len()
+    }
+
+    /// Nodes reachable by one edge from `id`. For undirected graphs, all adjacent nodes.
+    pub fn neighbors(&self, id: NodeId) -> &BitSet {
+        &self.outgoing[id]
+    }
+
+    /// Nodes with an edge to `id`. For undirected graphs, the same as [`Graph::neighbors`].
+    pub fn predecessors(&self, id: NodeId) -> &BitSet {
+        &self.incoming[id]
+    }
+
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.outgoing[from].contains(to)
+    }
+
+    /// Every edge once. Undirected edges are listed with the lower id first.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.nodes().flat_map(move |from| {
+            self.outgoing[from]
+                .iter()
+                .filter(move |&to| self.directed || from <= to)
+                .map(move |to| (from, to))
+        })
+    }
+
+    /// Groups of nodes connected by edges in either direction, each sorted by id.
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut seen = BitSet::new();
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                for next in self.outgoing[node].iter().chain(self.incoming[node].iter()) {
+                    if seen.insert(next) {
+                        component.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Orders all nodes so that every edge points forward.
+    pub fn toposort(&self) -> Result<Vec<NodeId>, Cycle<N>> {
+        self.toposort_within(&self.nodes().collect())
+    }
+
+    /// Orders the given nodes so that every edge between them points forward. Edges to other
+    /// nodes are ignored. Among nodes without constraints, lower ids come first.
+    pub fn toposort_within(&self, nodes: &BitSet) -> Result<Vec<NodeId>, Cycle<N>> {
+        let mut in_degree: HashMap<NodeId, usize> = nodes
+            .iter()
+            .map(|node| (node, (&self.incoming[node] & nodes).len()))
+            .collect();
+
+        // a min-heap, so the lowest id of all nodes that are ready comes next.
+        let mut ready: BinaryHeap<_> = nodes
+            .iter()
+            .filter(|node| in_degree[node] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(in_degree.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(node);
+            for next in (&self.outgoing[node] & nodes).iter() {
+                let degree = in_degree.get_mut(&next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        if order.len() == in_degree.len() {
+            return Ok(order);
+        }
+
+        // every node left has an unsorted predecessor, so walking backwards must close a cycle.
+        let mut remaining = nodes.clone();
+        for &node in &order {
+            remaining.remove(node);
+        }
+        let mut path = vec![remaining.first().unwrap()];
+        let mut position = HashMap::from([(path[0], 0)]);
+        loop {
+            let node = *path.last().unwrap();
+            let pred = (&self.incoming[node] & &remaining).first().unwrap();
+            if let Some(&start) = position.get(&pred) {
+                let mut cycle: Vec<_> = path[start..].iter().rev().copied().collect();
+                cycle.insert(0, pred);
+                return Err(Cycle(
+                    cycle
+                        .into_iter()
+                        .map(|id| self.labels[id].clone())
+                        .collect(),
+                ));
+            }
+            position.insert(pred, path.len());
+            path.push(pred);
+        }
+    }
+
+    /// All sets of three pairwise connected nodes, each sorted by id. Edge direction is ignored.
+    pub fn triangles(&self) -> Vec<[NodeId; 3]> {
+        let adjacent = |node: NodeId| {
+            let mut adjacent = self.outgoing[node].clone();
+            adjacent.union_with(&self.incoming[node]);
+            adjacent
+        };
+        let adjacency: Vec<_> = self.nodes().map(adjacent).collect();
+
+        let mut triangles = Vec::new();
+        for a in self.nodes() {
+            for b in adjacency[a].iter().filter(|&b| b > a) {
+                for c in (&adjacency[a] & &adjacency[b]).iter().filter(|&c| c > b) {
+                    triangles.push([a, b, c]);
+                }
+            }
+        }
+        triangles
+    }
+}
+
+impl<N: Clone + Eq + Hash + Display> Graph<N> {
+    /// Renders the graph in Graphviz DOT format, e.g. for `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let quote = |id: NodeId| format!("{:?}", self.labels[id].to_string());
+
+        let mut dot = format!("{keyword} {{\n");
+        for node in self.nodes() {
+            if self.outgoing[node].is_empty() && self.incoming[node].is_empty() {
+                writeln!(dot, "    {};", quote(node)).unwrap();
+            }
+        }
+        for (from, to) in self.edges() {
+            writeln!(dot, "    {} {arrow} {};", quote(from), quote(to)).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{BitSet, Cycle, Graph};
+
+    #[test]
+    fn tracks_bits() {
+        let mut set: BitSet = [3, 64, 130].into_iter().collect();
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert!(!set.insert(3));
+        assert_eq!(set.len(), 3);
+        assert!(set.remove(64));
+        assert!(!set.remove(1000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 130]);
+
+        let other: BitSet = [3, 4].into_iter().collect();
+        assert_eq!((&set & &other).iter().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn interns_labels() {
+        let graph = Graph::parse_undirected("a-b\nb-c\nd-e\n", "-").unwrap();
+        assert_eq!(graph.len(), 5);
+        let b = graph.id(&"b").unwrap();
+        assert_eq!(*graph.label(b), "b");
+        assert_eq!(graph.neighbors(b).len(), 2);
+        assert!(graph.has_edge(b, graph.id(&"a").unwrap()));
+        assert_eq!(graph.edges().count(), 3);
+    }
+
+    #[test]
+    fn reports_missing_separator() {
+        let err = Graph::parse_undirected("a-b\nc", "-").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "expected \"-\"");
+    }
+
+    #[test]
+    fn finds_components() {
+        let graph = Graph::parse_directed("a|b\nc|b\nd|e\n", "|").unwrap();
+        let components: Vec<Vec<_>> = graph
+            .components()
+            .into_iter()
+            .map(|c| c.into_iter().map(|id| *graph.label(id)).collect())
+            .collect();
+        assert_eq!(components, vec![vec!["a", "b", "c"], vec!["d", "e"]]);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = Graph::parse_directed("c|b\nb|a\nc|a\n", "|").unwrap();
+        let order: Vec<_> = graph
+            .toposort()
+            .unwrap()
+            .into_iter()
+            .map(|id| *graph.label(id))
+            .collect();
+        assert_eq!(order, vec!["c", "b", "a"]);
+
+        // lower ids first among the ready nodes: a FIFO queue would give 0, 1, 3, 2.
+        let mut graph = Graph::directed();
+        for node in 0..4 {
+            graph.add_node(node);
+        }
+        graph.extend_edges([(0, 3), (1, 2)]);
+        assert_eq!(graph.toposort().unwrap(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = Graph::parse_directed("x|a\na|b\nb|c\nc|a\n", "|").unwrap();
+        let cycle = graph.toposort().unwrap_err();
+        assert_eq!(cycle, Cycle(vec!["a", "b", "c", "a"]));
+        assert_eq!(cycle.to_string(), "graph has a cycle: a -> b -> c -> a");
+
+        // the cycle is not part of the subset.
+        let subset = [graph.id(&"x").unwrap(), graph.id(&"a").unwrap()]
+            .into_iter()
+            .collect();
+        assert!(graph.toposort_within(&subset).is_ok());
+    }
+
+    #[test]
+    fn enumerates_triangles() {
+        let graph = Graph::parse_undirected("a-b\nb-c\nc-a\nc-d\nb-d\nd-e", "-").unwrap();
+        let triangles: Vec<_> = graph
+            .triangles()
+            .into_iter()
+            .map(|t| t.map(|id| *graph.label(id)))
+            .collect();
+        assert_eq!(triangles, vec![["a", "b", "c"], ["b", "c", "d"]]);
+    }
+
+    #[test]
+    fn exports_dot() {
+        let mut graph = Graph::parse_directed("a|b", "|").unwrap();
+        graph.add_node("c");
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n    \"c\";\n    \"a\" -> \"b\";\n}\n"
+        );
+        let graph = Graph::parse_undirected("a-b", "-").unwrap();
+        assert_eq!(graph.to_dot(), "graph {\n    \"a\" -- \"b\";\n}\n");
+    }
+}
@@ -0,0 +1,602 @@
+/// A rectangular grid of tiles, as found in most puzzle inputs.
+///
+/// `Grid<T>` wraps an `ndarray::Array2<T>` indexed by `(row, column)`. It dereferences to the
+/// array, so `indexed_iter()`, `rows()`, `windows()` etc. work as usual. Tiles can be indexed by
+/// `(row, column)` or by [`Coord`]; the accessors taking a [`Coord`] return `None` outside the
+/// grid instead of panicking.
+///
+/// [`BitGrid`] and [`GridMap`] are the dense counterparts of `HashSet<Coord>` and
+/// `HashMap<Coord, V>` for positions within a grid. Keys can also carry a direction, see
+/// [`GridKey`].
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
+
+use ndarray::{s, Array2};
+
+use crate::{
+    geom::{Coord, Dir4},
+    parse::{Input, ParseError},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    tiles: Array2<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parses one row per line, mapping every char to a tile with `f`.
+    ///
+    /// # Panics
+    /// Panics if the input is empty or its lines differ in length, use [`Grid::try_parse`] for
+    /// puzzle inputs.
+    pub fn parse(input: &str, f: impl FnMut(char) -> T) -> Self {
+        Self::try_parse(&Input::new(input), input, f).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`Grid::parse`], but reports an empty `text` or a line of another length as an error
+    /// in `input`.
+    pub fn try_parse(
+        input: &Input,
+        text: &str,
+        mut f: impl FnMut(char) -> T,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut tiles = Vec::with_capacity(text.len());
+
+        for line in text.lines() {
+            let before = tiles.len();
+            tiles.extend(line.chars().map(&mut f));
+            let line_width = tiles.len() - before;
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(
+                        input.error(line, format!("expected {width} tiles, found {line_width}"))
+                    )
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        let width = width.ok_or_else(|| input.error(text, "expected a grid"))?;
+        Ok(Self::from_array(
+            Array2::from_shape_vec((height, width), tiles).unwrap(),
+        ))
+    }
+
+    pub fn from_array(tiles: Array2<T>) -> Self {
+        Self { tiles }
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.tiles
+    }
+
+    pub fn height(&self) -> usize {
+        self.tiles.nrows()
+    }
+
+    pub fn width(&self) -> usize {
+        self.tiles.ncols()
+    }
+
+    /// Converts a position to an array index, if it lies inside the grid.
+    pub fn checked_index(&self, pos: Coord) -> Option<(usize, usize)> {
+        let (y, x) = pos.checked_index()?;
+        (y < self.height() && x < self.width()).then_some((y, x))
+    }
+
+    pub fn contains(&self, pos: Coord) -> bool {
+        self.checked_index(pos).is_some()
+    }
+
+    pub fn get(&self, pos: Coord) -> Option<&T> {
+        self.checked_index(pos).map(|index| &self.tiles[index])
+    }
+
+    pub fn get_mut(&mut self, pos: Coord) -> Option<&mut T> {
+        self.checked_index(pos).map(|index| &mut self.tiles[index])
+    }
+
+    /// Positions of all tiles, in row-major order.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width();
+        (0..self.len()).map(move |i| Coord::from_index((i / width, i % width)))
+    }
+
+    fn expect_index(&self, pos: Coord) -> (usize, usize) {
+        self.checked_index(pos).unwrap_or_else(|| {
+            let (height, width) = self.tiles.dim();
+            panic!("{pos} is outside of the {width}x{height} grid")
+        })
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Position of the first tile equal to `tile`, in row-major order.
+    pub fn find(&self, tile: &T) -> Option<Coord> {
+        self.find_all(tile).next()
+    }
+
+    /// Positions of all tiles equal to `tile`, in row-major order.
+    pub fn find_all<'a>(&'a self, tile: &'a T) -> impl Iterator<Item = Coord> + 'a {
+        self.tiles
+            .indexed_iter()
+            .filter(move |(_, t)| *t == tile)
+            .map(|(index, _)| Coord::from_index(index))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Surrounds the grid with a border of `fill` that is `width` tiles wide.
+    ///
+    /// Tile `(y, x)` of the original grid is found at `(y + width, x + width)` afterwards.
+    pub fn padded(&self, width: usize, fill: T) -> Self {
+        let (height, cols) = self.tiles.dim();
+        let mut tiles = Array2::from_elem((height + 2 * width, cols + 2 * width), fill);
+        tiles
+            .slice_mut(s![width..width + height, width..width + cols])
+            .assign(&self.tiles);
+        Self { tiles }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.tiles[index]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.tiles[index]
+    }
+}
+
+/// # Panics
+/// Panics if the position is outside of the grid.
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Coord) -> &Self::Output {
+        &self.tiles[self.expect_index(pos)]
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, pos: Coord) -> &mut Self::Output {
+        let index = self.expect_index(pos);
+        &mut self.tiles[index]
+    }
+}
+
+impl<T> Deref for Grid<T> {
+    type Target = Array2<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tiles
+    }
+}
+
+impl<T> DerefMut for Grid<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tiles
+    }
+}
+
+/// Prints one line per row, e.g. to compare a grid with the puzzle description.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.tiles.rows().into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for tile in row {
+                write!(f, "{tile}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A key of a [`BitGrid`] or [`GridMap`]: a position plus one of `LAYERS` variants, e.g. the
+/// direction a walker faces.
+pub trait GridKey: Copy {
+    const LAYERS: usize;
+
+    fn coord(&self) -> Coord;
+    fn layer(&self) -> usize;
+    fn from_parts(coord: Coord, layer: usize) -> Self;
+}
+
+impl GridKey for Coord {
+    const LAYERS: usize = 1;
+
+    fn coord(&self) -> Coord {
+        *self
+    }
+
+    fn layer(&self) -> usize {
+        0
+    }
+
+    fn from_parts(coord: Coord, _: usize) -> Self {
+        coord
+    }
+}
+
+impl GridKey for (Coord, Dir4) {
+    const LAYERS: usize = 4;
+
+    fn coord(&self) -> Coord {
+        self.0
+    }
+
+    fn layer(&self) -> usize {
+        self.1 as usize
+    }
+
+    fn from_parts(coord: Coord, layer: usize) -> Self {
+        (coord, Dir4::ALL[layer])
+    }
+}
+
+/// Maps keys to slots of a flat vector, row by row and layer by layer within a tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Slots<K> {
+    width: usize,
+    height: usize,
+    key: PhantomData<K>,
+}
+
+impl<K: GridKey> Slots<K> {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            key: PhantomData,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.width * self.height * K::LAYERS
+    }
+
+    fn slot(&self, key: &K) -> Option<usize> {
+        let (row, col) = key.coord().checked_index()?;
+        (row < self.height && col < self.width)
+            .then(|| (row * self.width + col) * K::LAYERS + key.layer())
+    }
+
+    fn expect_slot(&self, key: &K) -> usize {
+        self.slot(key).unwrap_or_else(|| {
+            panic!(
+                "{} is outside of the {}x{} grid",
+                key.coord(),
+                self.width,
+                self.height
+            )
+        })
+    }
+
+    fn key(&self, slot: usize) -> K {
+        let (tile, layer) = (slot / K::LAYERS, slot % K::LAYERS);
+        K::from_parts(
+            Coord::from_index((tile / self.width, tile % self.width)),
+            layer,
+        )
+    }
+}
+
+/// A set of keys within a `width` x `height` grid, stored as one bit per key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid<K = Coord> {
+    slots: Slots<K>,
+    words: Vec<u64>,
+}
+
+impl<K: GridKey> BitGrid<K> {
+    pub fn new(width: usize, height: usize) -> Self {
+        let slots = Slots::new(width, height);
+        let words = vec![0; slots.len().div_ceil(64)];
+        Self { slots, words }
+    }
+
+    /// Returns whether the key was newly added.
+    ///
+    /// # Panics
+    /// Panics if the key is outside of the grid.
+    pub fn insert(&mut self, key: K) -> bool {
+        let slot = self.slots.expect_slot(&key);
+        let (word, bit) = (&mut self.words[slot / 64], 1 << (slot % 64));
+        let is_new = *word & bit == 0;
+        *word |= bit;
+        is_new
+    }
+
+    /// Returns whether the key was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        let Some(slot) = self.slots.slot(key) else {
+            return false;
+        };
+        let (word, bit) = (&mut self.words[slot / 64], 1 << (slot % 64));
+        let was_present = *word & bit != 0;
+        *word &= !bit;
+        was_present
+    }
+
+    /// Whether the key is in the set. Keys outside of the grid never are.
+    pub fn contains(&self, key: &K) -> bool {
+        self.slots
+            .slot(key)
+            .is_some_and(|slot| self.words[slot / 64] & (1 << (slot % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Keys in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    self.slots.key(i * 64 + bit)
+                })
+            })
+        })
+    }
+}
+
+impl<K: GridKey> Extend<K> for BitGrid<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, keys: I) {
+        for key in keys {
+            self.insert(key);
+        }
+    }
+}
+
+/// A map from keys within a `width` x `height` grid to values, stored in a flat vector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridMap<V, K = Coord> {
+    slots: Slots<K>,
+    values: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V, K: GridKey> GridMap<V, K> {
+    pub fn new(width: usize, height: usize) -> Self {
+        let slots = Slots::new(width, height);
+        let values = std::iter::repeat_with(|| None).take(slots.len()).collect();
+        Self {
+            slots,
+            values,
+            len: 0,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.values[self.slots.slot(key)?].as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.values[self.slots.slot(key)?].as_mut()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the previous value of the key.
+    ///
+    /// # Panics
+    /// Panics if the key is outside of the grid.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let slot = self.slots.expect_slot(&key);
+        let previous = self.values[slot].replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let previous = self.values[self.slots.slot(key)?].take();
+        if previous.is_some() {
+            self.len -= 1;
+        }
+        previous
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.values.fill_with(|| None);
+        self.len = 0;
+    }
+
+    /// Entries in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> {
+        self.values
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, value)| Some((self.slots.key(slot), value.as_ref()?)))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.values.iter().flatten()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{BitGrid, Grid, GridMap};
+    use crate::{
+        geom::{Coord, Dir4},
+        parse::Input,
+    };
+
+    const INPUT: &str = "#.#\n.S.\n#..\n";
+
+    #[test]
+    fn parses_and_displays() {
+        let grid = Grid::parse(INPUT, |c| c);
+        assert_eq!(grid.dim(), (3, 3));
+        assert_eq!(grid[(1, 1)], 'S');
+        assert_eq!(grid.to_string(), INPUT.trim_end());
+    }
+
+    #[test]
+    fn maps_tiles() {
+        let grid = Grid::parse("12\n34", |c| c.to_digit(10).unwrap());
+        assert_eq!(grid.sum(), 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_ragged_lines() {
+        Grid::parse("..\n.", |c| c);
+    }
+
+    #[test]
+    fn reports_ragged_lines() {
+        let text = "..\n.\n";
+        let err = Grid::try_parse(&Input::new(text), text, |c| c).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "expected 2 tiles, found 1");
+        assert!(Grid::try_parse(&Input::new(""), "", |c| c).is_err());
+    }
+
+    #[test]
+    fn finds_tiles() {
+        let grid = Grid::parse(INPUT, |c| c);
+        assert_eq!(grid.find(&'S'), Some(Coord::new(1, 1)));
+        assert_eq!(grid.find(&'E'), None);
+        assert_eq!(
+            grid.find_all(&'#').collect::<Vec<_>>(),
+            vec![Coord::new(0, 0), Coord::new(2, 0), Coord::new(0, 2)]
+        );
+    }
+
+    #[test]
+    fn checks_signed_positions() {
+        let mut grid = Grid::parse(INPUT, |c| c);
+        assert_eq!(grid.get(Coord::new(2, 2)), Some(&'.'));
+        assert_eq!(grid.get(Coord::new(0, -1)), None);
+        assert_eq!(grid.get(Coord::new(3, 0)), None);
+        assert!(!grid.contains(Coord::new(0, 3)));
+        *grid.get_mut(Coord::new(2, 1)).unwrap() = 'E';
+        assert_eq!(grid.find(&'E'), Some(Coord::new(2, 1)));
+        assert_eq!(grid[Coord::new(2, 1)], grid[(1, 2)]);
+        assert_eq!(grid.coords().nth(4), Some(Coord::new(1, 1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_index_outside() {
+        let grid = Grid::parse(INPUT, |c| c);
+        let _ = grid[Coord::new(-1, 0)];
+    }
+
+    #[test]
+    fn pads_grid() {
+        let grid = Grid::parse("ab\ncd", |c| c).padded(1, '.');
+        assert_eq!(grid.to_string(), "....\n.ab.\n.cd.\n....");
+    }
+
+    #[test]
+    fn tracks_bits() {
+        let mut set = BitGrid::new(10, 8);
+        assert!(set.insert(Coord::new(9, 7)));
+        assert!(!set.insert(Coord::new(9, 7)));
+        assert!(set.insert(Coord::new(1, 0)));
+        assert!(!set.contains(&Coord::new(-1, 0)));
+        assert!(!set.contains(&Coord::new(10, 0)));
+        assert_eq!(set.len(), 2);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Coord::new(1, 0), Coord::new(9, 7)]
+        );
+
+        let copy = set.clone();
+        set.extend([Coord::new(1, 0), Coord::new(2, 0)]);
+        assert_eq!(set.len(), 3);
+        assert!(set.remove(&Coord::new(1, 0)));
+        assert!(!set.remove(&Coord::new(20, 0)));
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(copy.len(), 2);
+    }
+
+    #[test]
+    fn tracks_directions() {
+        let mut set = BitGrid::new(3, 3);
+        set.insert((Coord::new(2, 1), Dir4::West));
+        assert!(set.contains(&(Coord::new(2, 1), Dir4::West)));
+        assert!(!set.contains(&(Coord::new(2, 1), Dir4::East)));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![(Coord::new(2, 1), Dir4::West)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_bits_outside() {
+        BitGrid::new(3, 3).insert(Coord::new(3, 0));
+    }
+
+    #[test]
+    fn maps_positions() {
+        let mut map = GridMap::new(4, 3);
+        assert_eq!(map.insert(Coord::new(3, 2), "a"), None);
+        assert_eq!(map.insert(Coord::new(3, 2), "b"), Some("a"));
+        map.insert(Coord::new(0, 1), "c");
+        assert_eq!(map.get(&Coord::new(3, 2)), Some(&"b"));
+        assert_eq!(map.get(&Coord::new(4, 2)), None);
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(Coord::new(0, 1), &"c"), (Coord::new(3, 2), &"b")]
+        );
+
+        *map.get_mut(&Coord::new(0, 1)).unwrap() = "d";
+        assert_eq!(map.remove(&Coord::new(0, 1)), Some("d"));
+        assert_eq!(map.values().collect::<Vec<_>>(), vec![&"b"]);
+        map.clear();
+        assert!(map.is_empty());
+        assert!(!map.contains_key(&Coord::new(3, 2)));
+    }
+}
@@ -1,3 +1,13 @@
+pub mod cycle;
+pub mod dsu;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod region;
+pub mod search;
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
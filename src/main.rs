@@ -1,4 +1,6 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, dashboard, download, inputs, read, scaffold, solve, status, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,7 +9,7 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{commands::inputs, Day};
     use std::process;
 
     pub enum AppArguments {
@@ -27,6 +29,7 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            args: Vec<String>,
         },
         All {
             release: bool,
@@ -35,6 +38,17 @@ mod args {
             all: bool,
             day: Option<Day>,
             store: bool,
+            args: Vec<String>,
+        },
+        Status {
+            run_tests: bool,
+            verify: bool,
+        },
+        Dashboard {
+            port: u16,
+        },
+        Inputs {
+            action: inputs::Action,
         },
         #[cfg(feature = "today")]
         Today,
@@ -43,7 +57,7 @@ mod args {
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let mut app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
             },
@@ -55,8 +69,19 @@ mod args {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    args: Vec::new(),
                 }
             }
+            Some("status") => AppArguments::Status {
+                run_tests: !args.contains("--no-test"),
+                verify: args.contains("--verify"),
+            },
+            Some("dashboard") => AppArguments::Dashboard {
+                port: args.opt_value_from_str("--port")?.unwrap_or(8024),
+            },
+            Some("inputs") => AppArguments::Inputs {
+                action: args.free_from_str()?,
+            },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
@@ -73,6 +98,7 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                args: Vec::new(),
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -87,7 +113,14 @@ mod args {
         };
 
         let remaining = args.finish();
-        if !remaining.is_empty() {
+        if let AppArguments::Solve { args, .. } | AppArguments::Time { args, .. } = &mut app_args {
+            // everything else is for the solution, e.g. a flag of one of its tools.
+            args.extend(
+                remaining
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().into_owned()),
+            );
+        } else if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
 
@@ -103,7 +136,15 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                args,
+            } => time::handle(day, all, store, &args),
+            AppArguments::Status { run_tests, verify } => status::handle(run_tests, verify),
+            AppArguments::Dashboard { port } => dashboard::handle(port),
+            AppArguments::Inputs { action } => inputs::handle(action),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -121,7 +162,8 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                args,
+            } => solve::handle(day, release, dhat, submit, &args),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
@@ -0,0 +1,294 @@
+/// Exact integer helpers for digit manipulation, number theory and small linear systems.
+///
+/// Everything here stays in integer arithmetic. Going through `f64` (e.g. `log10()`) silently
+/// breaks for values above 2^53, which puzzle answers reach regularly.
+const POW10: [u64; 20] = {
+    let mut pow = [1; 20];
+    let mut i = 1;
+    while i < pow.len() {
+        pow[i] = pow[i - 1] * 10;
+        i += 1;
+    }
+    pow
+};
+
+/// Number of decimal digits of `n`, where `0` has one digit.
+pub fn num_digits(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// The number whose digits are those of `lhs` followed by those of `rhs`, e.g. `12 || 345 = 12345`.
+/// Returns `None` on overflow.
+pub fn concat_digits(lhs: u64, rhs: u64) -> Option<u64> {
+    match POW10.get(num_digits(rhs) as usize) {
+        Some(&shift) => lhs.checked_mul(shift)?.checked_add(rhs),
+        None => (lhs == 0).then_some(rhs),
+    }
+}
+
+/// Splits a number with an even digit count into its halves, e.g. `1200` into `(12, 0)`.
+pub fn split_digits(n: u64) -> Option<(u64, u64)> {
+    let digits = num_digits(n);
+    digits.is_multiple_of(2).then(|| {
+        let separator = POW10[digits as usize / 2];
+        (n / separator, n % separator)
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `0` if either argument is `0`.
+///
+/// # Panics
+/// Panics if the result overflows.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b)).checked_mul(b).expect("lcm overflows u64")
+}
+
+/// Extended Euclid: returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)` and `g >= 0`.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m` in `0..m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Chinese remainder theorem for `x ≡ r (mod m)` for every `(r, m)`, with moduli that need not
+/// be coprime. Returns `(x, lcm)` with `0 <= x < lcm` of all moduli, or `None` if the congruences
+/// contradict each other or the lcm overflows.
+///
+/// # Panics
+/// Panics if a modulus is not positive.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut modulus) = (0_i128, 1_i128);
+    for &(r, m) in congruences {
+        assert!(m > 0, "crt modulus must be positive, got {m}");
+        let (r, m) = (i128::from(r).rem_euclid(i128::from(m)), i128::from(m));
+
+        // solve x + modulus * k ≡ r (mod m) for k.
+        let (g, inv, _) = egcd(i64::try_from(modulus % m).ok()?, i64::try_from(m).unwrap());
+        let (g, inv) = (i128::from(g), i128::from(inv));
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let k = ((r - x) / g % step * inv).rem_euclid(step);
+        x += modulus * k;
+        modulus *= step;
+        if modulus > i128::from(i64::MAX) {
+            return None;
+        }
+        x = x.rem_euclid(modulus);
+    }
+    Some((i64::try_from(x).unwrap(), i64::try_from(modulus).unwrap()))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Solutions of a 2×2 linear system, see [`solve_2x2`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Solutions2 {
+    /// The single solution `(x, y)`, which is integral.
+    Unique(i64, i64),
+    /// No integral solution: the equations contradict each other, or their single solution is
+    /// fractional.
+    None,
+    /// The equations are multiples of each other, so a whole line of rational values solves
+    /// them. Whether any of them is integral is left to the caller, e.g. via [`egcd`].
+    Dependent,
+}
+
+/// Solves `a[0][0] * x + a[0][1] * y = b[0]` and `a[1][0] * x + a[1][1] * y = b[1]` exactly,
+/// without dividing by a zero determinant.
+pub fn solve_2x2(a: [[i64; 2]; 2], b: [i64; 2]) -> Solutions2 {
+    let [[a11, a12], [a21, a22]] = a.map(|row| row.map(i128::from));
+    let [b1, b2] = b.map(i128::from);
+
+    let det = a11 * a22 - a12 * a21;
+    let det_x = b1 * a22 - a12 * b2;
+    let det_y = a11 * b2 - b1 * a21;
+
+    if det == 0 {
+        // consistent iff `b` lies in the span of the (parallel) columns.
+        let consistent = if a11 == 0 && a12 == 0 && a21 == 0 && a22 == 0 {
+            b1 == 0 && b2 == 0
+        } else {
+            det_x == 0 && det_y == 0
+        };
+        return if consistent {
+            Solutions2::Dependent
+        } else {
+            Solutions2::None
+        };
+    }
+
+    if det_x % det != 0 || det_y % det != 0 {
+        return Solutions2::None;
+    }
+    match (i64::try_from(det_x / det), i64::try_from(det_y / det)) {
+        (Ok(x), Ok(y)) => Solutions2::Unique(x, y),
+        _ => Solutions2::None,
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        concat_digits, crt, egcd, gcd, lcm, mod_inverse, num_digits, solve_2x2, split_digits,
+        Solutions2,
+    };
+
+    /// Deterministic pseudo-random numbers with a spread of magnitudes.
+    fn samples(count: usize) -> impl Iterator<Item = u64> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        (0..count).map(move |i| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state >> (i % 64)
+        })
+    }
+
+    #[test]
+    fn counts_digits_like_strings() {
+        for n in samples(10_000).chain([0, 9, 10, 99, 100, u64::MAX]) {
+            assert_eq!(num_digits(n) as usize, n.to_string().len(), "{n}");
+        }
+        for pow in 0..20 {
+            let n = 10_u64.pow(pow);
+            assert_eq!(num_digits(n - 1), pow.max(1), "{}", n - 1);
+            assert_eq!(num_digits(n), pow + 1, "{n}");
+        }
+    }
+
+    #[test]
+    fn concats_like_strings() {
+        let values: Vec<_> = samples(400).chain([0, 1, 10, 9_999_999_999]).collect();
+        for &lhs in &values {
+            for &rhs in &values {
+                let expected = format!("{lhs}{rhs}").parse::<u64>().ok();
+                assert_eq!(concat_digits(lhs, rhs), expected, "{lhs} || {rhs}");
+            }
+        }
+    }
+
+    #[test]
+    fn splits_like_strings() {
+        for n in samples(10_000).chain([0, 10, 1000, 9_999_999_999_999_999]) {
+            let s = n.to_string();
+            let expected = (s.len() % 2 == 0).then(|| {
+                let (upper, lower) = s.split_at(s.len() / 2);
+                (upper.parse().unwrap(), lower.parse().unwrap())
+            });
+            assert_eq!(split_digits(n), expected, "{n}");
+        }
+    }
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        let values: Vec<_> = samples(200).map(|n| n % 100_000).collect();
+        for &a in &values {
+            for &b in &values {
+                let g = gcd(a, b);
+                if g == 0 {
+                    assert_eq!((a, b), (0, 0));
+                    continue;
+                }
+                assert_eq!((a % g, b % g), (0, 0));
+                assert_eq!(gcd(a / g, b / g), 1);
+                assert_eq!(u128::from(lcm(a, b)) * u128::from(g), u128::from(a * b));
+
+                let (a, b) = (a as i64 - 50_000, b as i64);
+                let (g, x, y) = egcd(a, b);
+                assert_eq!(a * x + b * y, g);
+                assert_eq!(g as u64, gcd(a.unsigned_abs(), b.unsigned_abs()));
+            }
+        }
+    }
+
+    #[test]
+    fn inverts_modulo() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(4, 8), None);
+        for m in 2..200 {
+            for a in 0..m {
+                match mod_inverse(a, m) {
+                    Some(inv) => assert_eq!(a * inv % m, 1),
+                    None => assert_ne!(gcd(a as u64, m as u64), 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 101), (-1, 103)]), Some((10402, 10403)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        let moduli: Vec<_> = samples(300).map(|n| (n % 60 + 1) as i64).collect();
+        for pair in moduli.chunks(3) {
+            let x = 1_234_567;
+            let congruences: Vec<_> = pair.iter().map(|&m| (x % m, m)).collect();
+            let (y, modulus) = crt(&congruences).unwrap();
+            assert!(congruences.iter().all(|&(r, m)| y % m == r));
+            assert_eq!(x % modulus, y);
+        }
+    }
+
+    #[test]
+    fn solves_linear_systems() {
+        assert_eq!(
+            solve_2x2([[94, 22], [34, 67]], [8400, 5400]),
+            Solutions2::Unique(80, 40)
+        );
+        assert_eq!(solve_2x2([[2, 0], [0, 2]], [1, 2]), Solutions2::None);
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), Solutions2::Dependent);
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 7]), Solutions2::None);
+        assert_eq!(solve_2x2([[0, 0], [0, 0]], [0, 0]), Solutions2::Dependent);
+        assert_eq!(solve_2x2([[0, 0], [0, 0]], [0, 1]), Solutions2::None);
+        assert_eq!(solve_2x2([[0, 1], [0, 2]], [3, 6]), Solutions2::Dependent);
+
+        let values: Vec<_> = samples(1_200).map(|n| (n % 2_001) as i64 - 1_000).collect();
+        for v in values.chunks_exact(6) {
+            let a = [[v[0], v[1]], [v[2], v[3]]];
+            let b = [v[0] * v[4] + v[1] * v[5], v[2] * v[4] + v[3] * v[5]];
+            match solve_2x2(a, b) {
+                Solutions2::Unique(x, y) => assert_eq!((x, y), (v[4], v[5])),
+                Solutions2::Dependent => assert_eq!(v[0] * v[3], v[1] * v[2]),
+                Solutions2::None => panic!("{a:?} {b:?} has the solution {:?}", &v[4..]),
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+/// A cache for recursive solvers that compute each state once.
+///
+/// [`Memo`] replaces the usual get, compute, insert dance around a `HashMap`. Lookups accept
+/// borrowed keys (e.g. `&str` for a `Memo<String, _>`), and hits and misses are counted so the
+/// cache can be inspected with [`Memo::stats`], or printed by [`Memo::report`] when the
+/// `AOC_MEMO_STATS` environment variable is set.
+use std::{borrow::Borrow, collections::HashMap, env, fmt::Display, hash::Hash};
+
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub size: usize,
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lookups = self.hits + self.misses;
+        #[allow(clippy::cast_precision_loss)]
+        let hit_rate = if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64 * 100.0
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({hit_rate:.1}% hit rate), {} entries",
+            self.hits, self.misses, self.size
+        )
+    }
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up a cached value, counting the lookup as a hit or a miss.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let value = self.cache.get(key).cloned();
+        match value {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+        value
+    }
+
+    /// Caches `value` and returns it, so it can end a recursive call.
+    pub fn insert(&mut self, key: K, value: V) -> V {
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Returns the cached value for `key`, or computes and caches it with `f`.
+    ///
+    /// `f` receives the memo again, so it can recurse into smaller states.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f(self);
+        self.insert(key, value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.cache.len(),
+        }
+    }
+
+    /// Prints the cache statistics to stderr if `AOC_MEMO_STATS` is set.
+    pub fn report(&self, name: &str) {
+        if env::var_os("AOC_MEMO_STATS").is_some() {
+            eprintln!("memo {name}: {}", self.stats());
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Memo, Stats};
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_insert_with(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+    }
+
+    #[test]
+    fn memoizes_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2880067194370816120);
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 87,
+                misses: 89,
+                size: 89,
+            }
+        );
+        assert_eq!(
+            memo.stats().to_string(),
+            "87 hits, 89 misses (49.4% hit rate), 89 entries"
+        );
+    }
+
+    #[test]
+    fn looks_up_borrowed_keys() {
+        let mut memo: Memo<String, usize> = Memo::new();
+        assert_eq!(memo.get("abc"), None);
+        assert_eq!(memo.insert("abc".to_string(), 3), 3);
+        assert_eq!(memo.get("abc"), Some(3));
+        assert_eq!(memo.len(), 1);
+        assert_eq!(memo.stats().hits, 1);
+        assert_eq!(memo.stats().misses, 1);
+    }
+}
@@ -0,0 +1,231 @@
+/// Helpers for parsing puzzle inputs with errors that point at the offending line.
+///
+/// [`Input`] wraps the whole puzzle input. Its methods take slices of that input (as returned by
+/// `lines()`, `split_once()` etc.), so every [`ParseError`] knows the line and column it refers to.
+/// Parts can return `Result<T, ParseError>` and the runner prints the error with its line.
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in chars.
+    pub column: usize,
+    pub message: String,
+    /// The full input line the error points into.
+    pub text: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        writeln!(f, "    {}", self.text)?;
+        write!(f, "    {:>width$}", "^", width = self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Signed integer tokens in `text`, e.g. `["3", "-4"]` for `p=3,-4`.
+///
+/// A `-` directly before a digit is read as a sign, a `+` is not part of the token.
+pub fn int_tokens(text: &str) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let is_sign = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+            if is_sign || bytes[i].is_ascii_digit() {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                return Some(&text[start..i]);
+            }
+            i += 1;
+        }
+        None
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Copy, Debug)]
+pub struct Input<'a> {
+    text: &'a str,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// An error pointing at the start of `at`.
+    ///
+    /// # Panics
+    /// Panics if `at` is not a slice of this input.
+    pub fn error(&self, at: &str, message: impl Into<String>) -> ParseError {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|&offset| offset <= self.text.len())
+            .expect("error location must be a slice of the input");
+
+        let before = &self.text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+            text: self.text[line_start..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    /// Blocks of lines separated by blank lines, without their trailing newline.
+    pub fn sections(&self) -> impl Iterator<Item = &'a str> {
+        let text = self.text;
+        let mut sections = Vec::new();
+        let mut start = 0;
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                sections.push(&text[start..offset]);
+                start = offset + line.len();
+            }
+            offset += line.len();
+        }
+        sections.push(&text[start..]);
+        sections
+            .into_iter()
+            .map(str::trim_end)
+            .filter(|section| !section.is_empty())
+    }
+
+    pub fn split_once<'t>(
+        &self,
+        text: &'t str,
+        separator: &str,
+    ) -> Result<(&'t str, &'t str), ParseError> {
+        text.split_once(separator)
+            .ok_or_else(|| self.error(text, format!("expected \"{separator}\"")))
+    }
+
+    pub fn strip_prefix<'t>(&self, text: &'t str, prefix: &str) -> Result<&'t str, ParseError> {
+        text.strip_prefix(prefix)
+            .ok_or_else(|| self.error(text, format!("expected \"{prefix}\"")))
+    }
+
+    /// Parses `text` without its surrounding whitespace.
+    pub fn parse<T>(&self, text: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let text = text.trim();
+        text.parse()
+            .map_err(|err| self.error(text, format!("cannot parse \"{text}\": {err}")))
+    }
+
+    /// All signed integers in `text`, see [`int_tokens`].
+    pub fn ints<T>(&self, text: &str) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        int_tokens(text).map(|token| self.parse(token)).collect()
+    }
+
+    /// Exactly `N` signed integers in `text`, e.g. `[x, y, dx, dy]` for `p=0,4 v=3,-3`.
+    pub fn ints_n<T, const N: usize>(&self, text: &str) -> Result<[T; N], ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let ints = self.ints(text)?;
+        let count = ints.len();
+        ints.try_into()
+            .map_err(|_| self.error(text, format!("expected {N} integers, found {count}")))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{int_tokens, Input};
+
+    const INPUT: &str = "p=0,4 v=3,-3\nX+94, Y=-34\n\n\nlast 300\nsection\n";
+
+    #[test]
+    fn extracts_ints() {
+        let tokens: Vec<_> = int_tokens("p=0,4 v=3,-3 X+94 a-b 1-2").collect();
+        assert_eq!(tokens, vec!["0", "4", "3", "-3", "94", "1", "-2"]);
+
+        let input = Input::new(INPUT);
+        assert_eq!(
+            input.ints::<i32>(INPUT).unwrap(),
+            vec![0, 4, 3, -3, 94, -34, 300]
+        );
+        assert_eq!(input.ints_n::<i64, 2>("X+94, Y=-34").unwrap(), [94, -34]);
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = Input::new(INPUT);
+        let sections: Vec<_> = input.sections().collect();
+        assert_eq!(
+            sections,
+            vec!["p=0,4 v=3,-3\nX+94, Y=-34", "last 300\nsection"]
+        );
+        assert_eq!(Input::new("a\r\n\r\nb").sections().count(), 2);
+    }
+
+    #[test]
+    fn locates_errors() {
+        let input = Input::new(INPUT);
+        let line = INPUT.lines().nth(4).unwrap();
+        let (_, number) = input.split_once(line, " ").unwrap();
+        assert_eq!(input.parse::<u32>(number), Ok(300));
+
+        let err = input.parse::<u8>(number).unwrap_err();
+        assert_eq!((err.line, err.column), (5, 6));
+        assert_eq!(err.text, "last 300");
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 6: cannot parse \"300\": number too large to fit in target type\n    last 300\n         ^"
+        );
+    }
+
+    #[test]
+    fn reports_missing_parts() {
+        let input = Input::new(INPUT);
+        let line = INPUT.lines().nth(1).unwrap();
+        let err = input.strip_prefix(line, "Y=").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "expected \"Y=\"");
+
+        let err = input.ints_n::<i32, 3>(line).unwrap_err();
+        assert_eq!(err.message, "expected 3 integers, found 2");
+
+        let err = input.ints::<u8>(line).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 9));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_foreign_slices() {
+        Input::new(INPUT).error("elsewhere", "not part of the input");
+    }
+}
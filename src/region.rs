@@ -0,0 +1,141 @@
+/// Labeling of the connected regions of a grid.
+///
+/// [`label`] flood-fills 4-connected tiles that a predicate considers equivalent, e.g. garden
+/// plots of the same plant, and measures every region on the way: area, perimeter, corners (equal
+/// to the number of straight sides) and bounding box.
+use ndarray::Array2;
+
+use crate::{
+    geom::{Coord, Dir4},
+    grid::Grid,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Any tile of the region, the first one in row-major order.
+    pub origin: Coord,
+    pub area: usize,
+    /// Number of tile edges facing another region or the outside.
+    pub perimeter: usize,
+    /// Number of corners of the outline, including those of holes.
+    pub corners: usize,
+    /// Upper left and lower right tile of the bounding box.
+    pub min: Coord,
+    pub max: Coord,
+}
+
+#[derive(Clone, Debug)]
+pub struct Regions {
+    /// The index into `regions` of every tile.
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    /// The region containing `pos`, `None` outside of the grid.
+    pub fn region(&self, pos: Coord) -> Option<&Region> {
+        self.labels.get(pos).map(|&label| &self.regions[label])
+    }
+}
+
+/// Splits `grid` into regions, joining neighboring tiles `a` and `b` if `same(a, b)` holds.
+pub fn label<T>(grid: &Grid<T>, mut same: impl FnMut(&T, &T) -> bool) -> Regions {
+    const UNLABELED: usize = usize::MAX;
+    let mut labels = Grid::from_array(Array2::from_elem(grid.dim(), UNLABELED));
+    let mut regions = Vec::new();
+    let mut stack = Vec::new();
+
+    for origin in grid.coords() {
+        if labels[origin] != UNLABELED {
+            continue;
+        }
+
+        let label = regions.len();
+        labels[origin] = label;
+        stack.push(origin);
+        let mut tiles = Vec::new();
+        while let Some(pos) = stack.pop() {
+            tiles.push(pos);
+            for next in pos.neighbors4() {
+                let joins = grid
+                    .get(next)
+                    .is_some_and(|tile| labels[next] == UNLABELED && same(&grid[pos], tile));
+                if joins {
+                    labels[next] = label;
+                    stack.push(next);
+                }
+            }
+        }
+
+        let inside = |pos: Coord| labels.get(pos) == Some(&label);
+        let mut region = Region {
+            origin,
+            area: tiles.len(),
+            perimeter: 0,
+            corners: 0,
+            min: origin,
+            max: origin,
+        };
+        for &pos in &tiles {
+            region.min = Coord::new(region.min.x.min(pos.x), region.min.y.min(pos.y));
+            region.max = Coord::new(region.max.x.max(pos.x), region.max.y.max(pos.y));
+            for dir in Dir4::ALL {
+                let (side, next_side) = (pos.step(dir), pos.step(dir.turn_right()));
+                let diagonal = side + dir.turn_right().vector();
+                region.perimeter += usize::from(!inside(side));
+                // convex if both sides of the corner are outside, concave if only the diagonal is.
+                let convex = !inside(side) && !inside(next_side);
+                let concave = inside(side) && inside(next_side) && !inside(diagonal);
+                region.corners += usize::from(convex || concave);
+            }
+        }
+        regions.push(region);
+    }
+
+    Regions { labels, regions }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::label;
+    use crate::{geom::Coord, grid::Grid};
+
+    #[test]
+    fn measures_regions() {
+        let grid = Grid::parse("AAAA\nBBCD\nBBCC\nEEEC", |c| c);
+        let regions = label(&grid, |a, b| a == b);
+        let summary: Vec<_> = regions
+            .regions
+            .iter()
+            .map(|r| (grid[r.origin], r.area, r.perimeter, r.corners))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ]
+        );
+
+        let c = regions.region(Coord::new(3, 3)).unwrap();
+        assert_eq!((c.min, c.max), (Coord::new(2, 1), Coord::new(3, 3)));
+        assert_eq!(regions.labels[Coord::new(2, 2)], 2);
+        assert_eq!(regions.region(Coord::new(4, 0)), None);
+    }
+
+    #[test]
+    fn counts_corners_of_holes() {
+        // 4 outer corners, and 4 around each of the holes, which touch diagonally.
+        let grid = Grid::parse("AAAAAA\nABBAAA\nABBAAA\nAAABBA\nAAABBA\nAAAAAA", |c| c);
+        let regions = label(&grid, |a, b| a == b);
+        let corners: Vec<_> = regions.regions.iter().map(|r| r.corners).collect();
+        assert_eq!(corners, vec![12, 4, 4]);
+        assert_eq!(regions.regions[0].area, 28);
+        assert_eq!(regions.regions[0].perimeter, 40);
+    }
+}
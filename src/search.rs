@@ -0,0 +1,605 @@
+/// Shortest path searches over arbitrary states.
+///
+/// States can be anything hashable, e.g. a [`Coord`](crate::geom::Coord) or a `(Coord, Dir4)`
+/// pair. The graph is given by a closure listing the neighbors of a state (with the cost of
+/// reaching them for [`dijkstra`] and [`astar`]), so walls, bounds and turning rules stay in the
+/// solution.
+///
+/// Every search takes an `is_goal` closure and stops early once the closest goal is settled.
+/// Distances to states further away than that goal are incomplete then. Pass `|_| false` to
+/// explore everything that is reachable.
+///
+/// Distances are recorded in a `HashMap` by default. The `_in` variants take any other
+/// [`Ledger`], e.g. a [`GridMap`] for states on a bounded grid.
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use crate::grid::{GridKey, GridMap};
+
+/// Where a search records an [`Entry`] for each state it reaches.
+pub trait Ledger<S> {
+    type Entry;
+
+    fn get(&self, state: &S) -> Option<&Self::Entry>;
+    fn get_mut(&mut self, state: &S) -> Option<&mut Self::Entry>;
+    fn insert(&mut self, state: &S, entry: Self::Entry);
+}
+
+impl<S: Clone + Eq + Hash, E> Ledger<S> for HashMap<S, E> {
+    type Entry = E;
+
+    fn get(&self, state: &S) -> Option<&E> {
+        HashMap::get(self, state)
+    }
+
+    fn get_mut(&mut self, state: &S) -> Option<&mut E> {
+        HashMap::get_mut(self, state)
+    }
+
+    fn insert(&mut self, state: &S, entry: E) {
+        HashMap::insert(self, state.clone(), entry);
+    }
+}
+
+/// Panics if a state outside of the grid is reached, so `neighbors` must respect the bounds.
+impl<K: GridKey, E> Ledger<K> for GridMap<E, K> {
+    type Entry = E;
+
+    fn get(&self, state: &K) -> Option<&E> {
+        GridMap::get(self, state)
+    }
+
+    fn get_mut(&mut self, state: &K) -> Option<&mut E> {
+        GridMap::get_mut(self, state)
+    }
+
+    fn insert(&mut self, state: &K, entry: E) {
+        GridMap::insert(self, *state, entry);
+    }
+}
+
+/// What a search records for a state: its distance as a `usize`, or a [`Visit`] when the
+/// predecessors are tracked as well.
+pub trait Entry<S> {
+    /// The entry of a state reached at `distance`, from `pred` unless it is a start.
+    fn reached(distance: usize, pred: Option<&S>) -> Self;
+
+    fn distance(&self) -> usize;
+
+    /// Records that the state is also reached from `pred` at its distance.
+    fn tie(&mut self, pred: &S);
+}
+
+impl<S> Entry<S> for usize {
+    fn reached(distance: usize, _: Option<&S>) -> Self {
+        distance
+    }
+
+    fn distance(&self) -> usize {
+        *self
+    }
+
+    fn tie(&mut self, _: &S) {}
+}
+
+/// The distance to a state and the states it is reached from on its shortest paths.
+///
+/// Kept in the same entry as the distance, so that tracking predecessors costs no lookups of its
+/// own.
+pub struct Visit<S> {
+    distance: usize,
+    preds: Preds<S>,
+}
+
+impl<S: Clone> Entry<S> for Visit<S> {
+    fn reached(distance: usize, pred: Option<&S>) -> Self {
+        let preds = pred.map_or(Preds::None, |pred| Preds::One(pred.clone()));
+        Self { distance, preds }
+    }
+
+    fn distance(&self) -> usize {
+        self.distance
+    }
+
+    fn tie(&mut self, pred: &S) {
+        self.preds.push(pred.clone());
+    }
+}
+
+/// Predecessors of a state. Most states have a single one, which is stored without allocating.
+enum Preds<S> {
+    /// A start, the beginning of all of its shortest paths.
+    None,
+    One(S),
+    Many(Vec<S>),
+}
+
+impl<S: Clone> Preds<S> {
+    fn as_slice(&self) -> &[S] {
+        match self {
+            Preds::None => &[],
+            Preds::One(pred) => std::slice::from_ref(pred),
+            Preds::Many(preds) => preds,
+        }
+    }
+
+    fn push(&mut self, pred: S) {
+        match self {
+            Preds::None => {}
+            Preds::One(first) => *self = Preds::Many(vec![first.clone(), pred]),
+            Preds::Many(preds) => preds.push(pred),
+        }
+    }
+}
+
+/// Records reaching `state` at `cost` from `pred`, unless it is known to be closer. Returns how
+/// `cost` compares to the known distance, `Less` for a state reached for the first time.
+fn relax<S, L>(ledger: &mut L, state: &S, cost: usize, pred: Option<&S>) -> Ordering
+where
+    L: Ledger<S>,
+    L::Entry: Entry<S>,
+{
+    let Some(known) = ledger.get_mut(state) else {
+        ledger.insert(state, L::Entry::reached(cost, pred));
+        return Ordering::Less;
+    };
+    let ordering = cost.cmp(&known.distance());
+    match (ordering, pred) {
+        (Ordering::Less, _) => *known = L::Entry::reached(cost, pred),
+        (Ordering::Equal, Some(pred)) => known.tie(pred),
+        _ => {}
+    }
+    ordering
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The result of a search: an [`Entry`] for every reachable state.
+pub struct Search<S, L = HashMap<S, usize>> {
+    ledger: L,
+    state: PhantomData<S>,
+}
+
+impl<S, L> Search<S, L>
+where
+    L: Ledger<S>,
+    L::Entry: Entry<S>,
+{
+    fn new(ledger: L) -> Self {
+        Self {
+            ledger,
+            state: PhantomData,
+        }
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.ledger.get(state).map(Entry::distance)
+    }
+
+    pub fn distances(&self) -> &L {
+        &self.ledger
+    }
+
+    pub fn into_distances(self) -> L {
+        self.ledger
+    }
+}
+
+impl<S, L> Search<S, L>
+where
+    S: Clone + Eq + Hash,
+    L: Ledger<S, Entry = Visit<S>>,
+{
+    /// The states from which `state` is reached on a shortest path.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.ledger
+            .get(state)
+            .map_or(&[], |visit| visit.preds.as_slice())
+    }
+
+    /// One shortest path from a start state to `goal`, including both ends.
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        self.ledger.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while let Some(pred) = self.predecessors(path.last().unwrap()).first() {
+            path.push(pred.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every state that lies on any shortest path to one of `goals`.
+    pub fn on_shortest_paths<'a>(&self, goals: impl IntoIterator<Item = &'a S>) -> HashSet<S>
+    where
+        S: 'a,
+    {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<S> = goals
+            .into_iter()
+            .filter(|goal| self.ledger.get(goal).is_some())
+            .cloned()
+            .collect();
+        while let Some(state) = stack.pop() {
+            if seen.insert(state.clone()) {
+                stack.extend(self.predecessors(&state).iter().cloned());
+            }
+        }
+        seen
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Breadth-first search from `starts`, where every step costs 1.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs_in(HashMap::new(), starts, neighbors, is_goal)
+}
+
+/// Like [`bfs`], but records distances in `ledger`, which should be empty.
+pub fn bfs_in<S, I, L>(
+    mut ledger: L,
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, L>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    L: Ledger<S, Entry = usize>,
+{
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if relax(&mut ledger, &start, 0, None) == Ordering::Less {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            break;
+        }
+
+        let next_cost = ledger.get(&state).unwrap() + 1;
+        for next in neighbors(&state) {
+            if relax(&mut ledger, &next, next_cost, None) == Ordering::Less {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Search::new(ledger)
+}
+
+/// Dijkstra's algorithm from `starts`. `neighbors` lists each neighbor with the cost of the step.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    run_dijkstra(HashMap::new(), starts, neighbors, is_goal)
+}
+
+/// Like [`dijkstra`], but records distances in `ledger`, which should be empty.
+pub fn dijkstra_in<S, I, L>(
+    ledger: L,
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, L>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+    L: Ledger<S, Entry = usize>,
+{
+    run_dijkstra(ledger, starts, neighbors, is_goal)
+}
+
+/// Like [`dijkstra`], but also records every predecessor on a shortest path, so that
+/// [`Search::on_shortest_paths`] and [`Search::path_to`] can be used.
+///
+/// When stopping early, all goals as close as the closest one are settled with their
+/// predecessors, so paths to every optimal goal can be recovered.
+pub fn dijkstra_with_predecessors<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, HashMap<S, Visit<S>>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    run_dijkstra(HashMap::new(), starts, neighbors, is_goal)
+}
+
+/// Like [`dijkstra_with_predecessors`], but records the visits in `ledger`, which should be
+/// empty.
+pub fn dijkstra_with_predecessors_in<S, I, L>(
+    ledger: L,
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, L>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+    L: Ledger<S, Entry = Visit<S>>,
+{
+    run_dijkstra(ledger, starts, neighbors, is_goal)
+}
+
+fn run_dijkstra<S, I, L>(
+    mut ledger: L,
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, L>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+    L: Ledger<S>,
+    L::Entry: Entry<S>,
+{
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if relax(&mut ledger, &start, 0, None) == Ordering::Less {
+            queue.push(Candidate::new(0, 0, start));
+        }
+    }
+
+    let mut goal_cost = None;
+
+    while let Some(Candidate { cost, state, .. }) = queue.pop() {
+        if ledger
+            .get(&state)
+            .is_some_and(|known| cost > known.distance())
+        {
+            continue;
+        }
+        if goal_cost.is_some_and(|goal_cost| cost > goal_cost) {
+            break;
+        }
+        if goal_cost.is_none() && is_goal(&state) {
+            goal_cost = Some(cost);
+        }
+
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if relax(&mut ledger, &next, next_cost, Some(&state)) == Ordering::Less {
+                queue.push(Candidate::new(next_cost, next_cost, next));
+            }
+        }
+    }
+
+    Search::new(ledger)
+}
+
+/// A* search from `start` to the first state satisfying `is_goal`. `heuristic` must never
+/// overestimate the remaining cost. Returns the cost and the path, including both ends.
+pub fn astar<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut dist = HashMap::new();
+    let mut parents: HashMap<S, S> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    dist.insert(start.clone(), 0);
+    queue.push(Candidate::new(heuristic(&start), 0, start));
+
+    while let Some(Candidate { cost, state, .. }) = queue.pop() {
+        if cost > dist[&state] {
+            continue;
+        }
+
+        if is_goal(&state) {
+            let mut path = vec![state];
+            while let Some(parent) = parents.get(path.last().unwrap()) {
+                path.push(parent.clone());
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if dist.get(&next).is_none_or(|&known| next_cost < known) {
+                dist.insert(next.clone(), next_cost);
+                parents.insert(next.clone(), state.clone());
+                let estimate = next_cost + heuristic(&next);
+                queue.push(Candidate::new(estimate, next_cost, next));
+            }
+        }
+    }
+
+    None
+}
+
+/// Heap entry ordered by lowest `priority` first. For Dijkstra, the priority is the cost so far,
+/// for A* the estimated total cost. Among equal priorities, deeper entries are preferred.
+struct Candidate<S> {
+    priority: usize,
+    cost: usize,
+    state: S,
+}
+
+impl<S> Candidate<S> {
+    fn new(priority: usize, cost: usize, state: S) -> Self {
+        Self {
+            priority,
+            cost,
+            state,
+        }
+    }
+}
+
+impl<S> PartialEq for Candidate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.cost == other.cost
+    }
+}
+
+impl<S> Eq for Candidate<S> {}
+
+impl<S> Ord for Candidate<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl<S> PartialOrd for Candidate<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{astar, bfs, bfs_in, dijkstra, dijkstra_with_predecessors};
+    use crate::{
+        geom::Coord,
+        grid::{Grid, GridMap},
+    };
+
+    const MAZE: &str = "\
+S..#
+.#..
+...E";
+
+    fn open_neighbors(grid: &Grid<char>, pos: &Coord) -> Vec<Coord> {
+        pos.neighbors4()
+            .filter(|&n| grid.get(n).is_some_and(|&c| c != '#'))
+            .collect()
+    }
+
+    #[test]
+    fn bfs_counts_steps() {
+        let grid = Grid::parse(MAZE, |c| c);
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let search = bfs([start], |pos| open_neighbors(&grid, pos), |_| false);
+        assert_eq!(search.distance(&start), Some(0));
+        assert_eq!(search.distance(&end), Some(5));
+        assert_eq!(search.distance(&Coord::new(3, 0)), None);
+    }
+
+    #[test]
+    fn records_in_grid_map() {
+        let grid = Grid::parse(MAZE, |c| c);
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let ledger = GridMap::new(grid.width(), grid.height());
+        let search = bfs_in(ledger, [start], |pos| open_neighbors(&grid, pos), |_| false);
+        assert_eq!(search.distance(&end), Some(5));
+        assert_eq!(search.distances().len(), 10);
+        assert_eq!(search.distances().get(&Coord::new(1, 1)), None);
+    }
+
+    #[test]
+    fn stops_at_goal() {
+        let search = bfs([0], |&n: &u32| [n + 1], |&n| n == 10);
+        assert_eq!(search.distance(&10), Some(10));
+        assert_eq!(search.distances().len(), 11);
+
+        let search = dijkstra([0], |&n: &u32| [(n + 1, 2)], |&n| n == 10);
+        assert_eq!(search.distance(&10), Some(20));
+        assert!(search.distances().len() < 15);
+    }
+
+    #[test]
+    fn dijkstra_weighs_steps() {
+        // 0 -> 1 -> 3 costs 2, 0 -> 2 -> 3 costs 4, 0 -> 3 costs 5.
+        let edges = [(0, 1, 1), (1, 3, 1), (0, 2, 1), (2, 3, 3), (0, 3, 5)];
+        let search = dijkstra(
+            [0],
+            |&n: &u32| {
+                edges
+                    .iter()
+                    .filter(move |(from, _, _)| *from == n)
+                    .map(|&(_, to, cost)| (to, cost))
+            },
+            |_| false,
+        );
+        assert_eq!(search.distance(&3), Some(2));
+        assert_eq!(search.distance(&2), Some(1));
+    }
+
+    #[test]
+    fn recovers_all_shortest_paths() {
+        let grid = Grid::parse(MAZE, |c| c);
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let search = dijkstra_with_predecessors(
+            [start],
+            |pos| open_neighbors(&grid, pos).into_iter().map(|n| (n, 1)),
+            |pos| *pos == end,
+        );
+
+        // every open tile lies on one of the routes around the inner wall.
+        let tiles = search.on_shortest_paths([&end]);
+        let expected: HashSet<_> = grid.coords().filter(|&c| grid[c] != '#').collect();
+        assert_eq!(tiles, expected);
+
+        let path = search.path_to(&end).unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+    }
+
+    #[test]
+    fn astar_finds_optimal_path() {
+        let grid = Grid::parse(MAZE, |c| c);
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let (cost, path) = astar(
+            start,
+            |pos| open_neighbors(&grid, pos).into_iter().map(|n| (n, 1)),
+            |pos| pos.manhattan(end) as usize,
+            |pos| *pos == end,
+        )
+        .unwrap();
+        assert_eq!(cost, 5);
+        assert_eq!(path.len(), 6);
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+    }
+
+    #[test]
+    fn astar_reports_unreachable_goal() {
+        let grid = Grid::parse(MAZE, |c| c);
+        let start = grid.find(&'S').unwrap();
+        let result = astar(
+            start,
+            |pos| open_neighbors(&grid, pos).into_iter().map(|n| (n, 1)),
+            |_| 0,
+            |pos| *pos == Coord::new(3, 0),
+        );
+        assert!(result.is_none());
+    }
+}
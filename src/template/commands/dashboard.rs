@@ -0,0 +1,461 @@
+/// Serves a small dashboard with the progress of every day on localhost.
+/// Uses a blocking, thread-per-connection HTTP/1.1 server built on `std::net`.
+use std::{
+    collections::HashSet,
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    process,
+    sync::{Mutex, TryLockError},
+    thread,
+};
+
+use crate::template::{
+    all_days,
+    config::config,
+    run_multi::{run_multi_to, OutputSink},
+    sealed,
+    status::{all_statuses, DayStatus},
+    timings::TimingsHistory,
+    Day,
+};
+
+const STYLE: &str = "
+body { background: #0f0f23; color: #cccccc; font-family: 'Source Code Pro', monospace; margin: 2em; }
+a { color: #009900; text-decoration: none; }
+a:hover { color: #99ff99; }
+h1, h2 { color: #00cc00; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.8em; text-align: left; }
+tr:nth-child(even) { background: #10101a; }
+pre { white-space: pre-wrap; max-width: 60em; }
+button { background: #10101a; color: #ffff66; border: 1px solid #666666; cursor: pointer; padding: 0.3em 1em; }
+svg polyline { fill: none; stroke: #ffff66; stroke-width: 1.5; }
+svg circle { fill: #ffff66; }
+svg text { fill: #666666; font-size: 10px; }
+.ok { color: #00cc00; }
+.fail { color: #cc0000; }
+.none { color: #666666; }
+";
+
+enum Route {
+    Index,
+    Day(Day),
+    Run(Option<Day>),
+    NotFound,
+}
+
+impl Route {
+    fn parse(method: &str, path: &str) -> Self {
+        match (method, path) {
+            ("GET", "/") => Route::Index,
+            ("POST", "/run/all") => Route::Run(None),
+            ("GET", path) => path
+                .strip_prefix("/day/")
+                .and_then(|day| day.parse().ok())
+                .map_or(Route::NotFound, Route::Day),
+            ("POST", path) => path
+                .strip_prefix("/run/")
+                .and_then(|day| day.parse().ok())
+                .map_or(Route::NotFound, |day| Route::Run(Some(day))),
+            _ => Route::NotFound,
+        }
+    }
+}
+
+/// The request headers the dashboard looks at.
+#[derive(Default)]
+struct Headers {
+    host: Option<String>,
+    origin: Option<String>,
+}
+
+impl Headers {
+    fn parse_line(&mut self, line: &str) {
+        let Some((name, value)) = line.split_once(':') else {
+            return;
+        };
+        let value = Some(value.trim().to_ascii_lowercase());
+        match name.trim().to_ascii_lowercase().as_str() {
+            "host" => self.host = value,
+            "origin" => self.origin = value,
+            _ => {}
+        }
+    }
+
+    /// Whether the request was addressed to the dashboard on `port` by one of its own pages.
+    /// Checking `Host` keeps other sites out through a DNS name rebound to 127.0.0.1, checking
+    /// `Origin` keeps them from posting a form to it.
+    fn is_local(&self, port: u16) -> bool {
+        let Some(host) = &self.host else {
+            return false;
+        };
+        let hosts = [format!("127.0.0.1:{port}"), format!("localhost:{port}")];
+        hosts.contains(host)
+            && self
+                .origin
+                .as_ref()
+                .is_none_or(|origin| *origin == format!("http://{host}"))
+    }
+}
+
+/// Held while a run streams its output, runs in parallel would compete for the CPU and skew
+/// their timings.
+static RUNNING: Mutex<()> = Mutex::new(());
+
+pub fn handle(port: u16) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to bind to port {port}: {e}");
+            process::exit(1);
+        }
+    };
+
+    println!("🎄 Dashboard running on http://127.0.0.1:{port}/ (press Ctrl+C to stop).");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, port) {
+                        eprintln!("Failed to handle request: {e}");
+                    }
+                });
+            }
+            Err(e) => eprintln!("Failed to accept connection: {e}"),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, port: u16) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut headers = Headers::default();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        headers.parse_line(&header);
+    }
+
+    if !headers.is_local(port) {
+        return respond(&mut stream, "403 Forbidden", &page("Forbidden", ""));
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let route = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => Route::parse(method, path),
+        _ => Route::NotFound,
+    };
+
+    match route {
+        Route::Index => respond(&mut stream, "200 OK", &index_page()),
+        Route::Day(day) => respond(&mut stream, "200 OK", &day_page(day)),
+        Route::Run(day) => stream_run(stream, day),
+        Route::NotFound => respond(&mut stream, "404 Not Found", &page("Not found", "")),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Streams the output of a run to the browser, using chunked transfer encoding.
+struct HttpSink {
+    stream: Mutex<TcpStream>,
+}
+
+impl HttpSink {
+    fn write_chunk(&self, data: &str) {
+        if data.is_empty() {
+            return;
+        }
+        let mut stream = self.stream.lock().unwrap();
+        // a closed connection only means that nobody is watching anymore.
+        let _ = write!(stream, "{:x}\r\n{data}\r\n", data.len()).and_then(|()| stream.flush());
+    }
+}
+
+impl OutputSink for HttpSink {
+    fn stdout(&self, line: &str) {
+        self.write_chunk(&format!("{}\n", strip_ansi(line)));
+    }
+
+    fn stderr(&self, line: &str) {
+        self.write_chunk(&format!("{}\n", strip_ansi(line)));
+    }
+}
+
+fn stream_run(mut stream: TcpStream, day: Option<Day>) -> io::Result<()> {
+    // a run that panicked has still finished.
+    let _running = match RUNNING.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => {
+            let body = "<p>Another run is in progress, try again once it has finished.</p>";
+            return respond(&mut stream, "409 Conflict", &page("Busy", body));
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nX-Content-Type-Options: nosniff\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+    )?;
+
+    let days: HashSet<Day> = day.map_or_else(|| all_days().collect(), |day| HashSet::from([day]));
+
+    let sink = HttpSink {
+        stream: Mutex::new(stream),
+    };
+    run_multi_to(&days, true, false, &[], &sink);
+
+    let mut stream = sink.stream.into_inner().unwrap();
+    write!(stream, "0\r\n\r\n")?;
+    stream.flush()
+}
+
+fn strip_ansi(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip a CSI sequence like `\x1b[1m` up to and including its final letter.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title><style>{STYLE}</style></head>\n<body>{body}</body></html>\n"
+    )
+}
+
+fn check(value: bool) -> &'static str {
+    if value {
+        "<span class=\"ok\">✔</span>"
+    } else {
+        "<span class=\"fail\">✖</span>"
+    }
+}
+
+/// Renders the total run time of a day over time as an SVG line chart.
+fn history_chart(points: &[(u64, f64)], width: u32, height: u32, labels: bool) -> String {
+    if points.is_empty() {
+        return "<span class=\"none\">-</span>".into();
+    }
+
+    let max = points.iter().map(|p| p.1).fold(f64::MIN_POSITIVE, f64::max);
+    let margin = 4.0;
+    let (w, h) = (
+        f64::from(width) - 2.0 * margin,
+        f64::from(height) - 2.0 * margin,
+    );
+    #[allow(clippy::cast_precision_loss)]
+    let step = if points.len() > 1 {
+        w / (points.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    #[allow(clippy::cast_precision_loss)]
+    let coords: Vec<(f64, f64)> = points
+        .iter()
+        .enumerate()
+        .map(|(i, &(_, nanos))| (margin + step * i as f64, margin + h - h * nanos / max))
+        .collect();
+
+    let mut svg = format!("<svg width=\"{width}\" height=\"{height}\">");
+    if coords.len() == 1 {
+        let _ = write!(
+            svg,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"2\"/>",
+            coords[0].0, coords[0].1
+        );
+    } else {
+        let polyline: Vec<_> = coords
+            .iter()
+            .map(|(x, y)| format!("{x:.1},{y:.1}"))
+            .collect();
+        let _ = write!(svg, "<polyline points=\"{}\"/>", polyline.join(" "));
+    }
+    if labels {
+        let _ = write!(
+            svg,
+            "<text x=\"{margin}\" y=\"12\">{:.2}ms</text><text x=\"{margin}\" y=\"{}\">{} runs</text>",
+            max / 1_000_000_f64,
+            height - 4,
+            points.len()
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn status_row(status: &DayStatus, history: &TimingsHistory) -> String {
+    let day = status.day;
+    let (part_1, part_2) = status.timing.as_ref().map_or(("-", "-"), |t| {
+        (
+            t.part_1.as_deref().unwrap_or("-"),
+            t.part_2.as_deref().unwrap_or("-"),
+        )
+    });
+    let examples = if status.examples == 0 {
+        "<span class=\"none\">-</span>".into()
+    } else {
+        status.examples.to_string()
+    };
+
+    format!(
+        "<tr><td><a href=\"/day/{day}\">Day {day}</a></td><td>{}</td><td>{}</td><td>{examples}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+        check(status.scaffolded),
+        check(status.has_input),
+        status.answers.len(),
+        escape_html(part_1),
+        escape_html(part_2),
+        history_chart(&history.for_day(day), 120, 24, false),
+    )
+}
+
+fn index_page() -> String {
+    let statuses = all_statuses();
+    let history = TimingsHistory::read_from_file();
+
+    let rows: String = statuses
+        .iter()
+        .map(|status| status_row(status, &history))
+        .collect();
+
+    let total_millis: f64 = statuses
+        .iter()
+        .filter_map(|s| s.timing.as_ref())
+        .map(|t| t.total_nanos)
+        .sum::<f64>()
+        / 1_000_000_f64;
+
+    let title = config().year().map_or_else(
+        || "Advent of Code".into(),
+        |y| format!("Advent of Code {y}"),
+    );
+
+    let body = format!(
+        "<h1>🎄 {title}</h1>\
+        <form method=\"post\" action=\"/run/all\"><button>Run all</button></form>\
+        <table><tr><th>Day</th><th>Solution</th><th>Input</th><th>Examples</th><th>Answers</th><th>Part 1</th><th>Part 2</th><th>History</th></tr>{rows}</table>\
+        <p><b>Total: {total_millis:.2}ms</b></p>"
+    );
+
+    page(&title, &body)
+}
+
+fn day_page(day: Day) -> String {
+    let history = TimingsHistory::read_from_file();
+
+    let puzzle = sealed::read_to_string(&config().puzzle_path(day)).map_or_else(
+        |_| format!("<p class=\"none\">No puzzle description found. Run <code>cargo download {day}</code> to fetch it.</p>"),
+        |markdown| format!("<pre>{}</pre>", escape_html(&markdown)),
+    );
+
+    let body = format!(
+        "<p><a href=\"/\">← All days</a></p><h1>Day {day}</h1>\
+        <form method=\"post\" action=\"/run/{day}\"><button>Run</button></form>\
+        <h2>History</h2>{}\
+        <h2>Puzzle</h2>{puzzle}",
+        history_chart(&history.for_day(day), 600, 160, true)
+    );
+
+    page(&format!("Day {day}"), &body)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{escape_html, history_chart, strip_ansi, Headers, Route};
+    use crate::day;
+
+    #[test]
+    fn parses_routes() {
+        assert!(matches!(Route::parse("GET", "/"), Route::Index));
+        assert!(matches!(Route::parse("GET", "/day/04"), Route::Day(d) if d == day!(4)));
+        assert!(matches!(Route::parse("POST", "/run/4"), Route::Run(Some(d)) if d == day!(4)));
+        assert!(matches!(Route::parse("POST", "/run/all"), Route::Run(None)));
+        assert!(matches!(Route::parse("GET", "/day/26"), Route::NotFound));
+        assert!(matches!(Route::parse("GET", "/run/4"), Route::NotFound));
+    }
+
+    fn headers(lines: &[&str]) -> Headers {
+        let mut headers = Headers::default();
+        for line in lines {
+            headers.parse_line(line);
+        }
+        headers
+    }
+
+    #[test]
+    fn accepts_only_local_requests() {
+        assert!(headers(&["Host: 127.0.0.1:8080"]).is_local(8080));
+        assert!(headers(&["host: LOCALHOST:8080", "Origin: http://localhost:8080"]).is_local(8080));
+        assert!(!headers(&[]).is_local(8080));
+        assert!(!headers(&["Host: 127.0.0.1:8081"]).is_local(8080));
+        assert!(!headers(&["Host: attacker.example:8080"]).is_local(8080));
+        assert!(
+            !headers(&["Host: 127.0.0.1:8080", "Origin: http://attacker.example"]).is_local(8080)
+        );
+        assert!(
+            !headers(&["Host: 127.0.0.1:8080", "Origin: http://localhost:8080"]).is_local(8080)
+        );
+        assert!(!headers(&["Host: 127.0.0.1:8080", "Origin: null"]).is_local(8080));
+    }
+
+    #[test]
+    fn strips_ansi_sequences() {
+        assert_eq!(
+            strip_ansi("Part 1: \x1b[1m42\x1b[0m (1ms)"),
+            "Part 1: 42 (1ms)"
+        );
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn renders_history_charts() {
+        assert!(history_chart(&[], 100, 20, false).contains('-'));
+        assert!(history_chart(&[(0, 1.0)], 100, 20, false).contains("<circle"));
+        assert!(history_chart(&[(0, 1.0), (1, 2.0)], 100, 20, false).contains("<polyline"));
+    }
+}
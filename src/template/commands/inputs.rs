@@ -0,0 +1,219 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    str::FromStr,
+};
+
+use crate::template::{config::config, sealed};
+
+pub enum Action {
+    Encrypt,
+    Decrypt,
+    Check,
+    Keygen,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "encrypt" => Ok(Action::Encrypt),
+            "decrypt" => Ok(Action::Decrypt),
+            "check" => Ok(Action::Check),
+            "keygen" => Ok(Action::Keygen),
+            x => Err(format!(
+                "unknown action \"{x}\", expecting one of encrypt, decrypt, check or keygen."
+            )),
+        }
+    }
+}
+
+/// Folders that hold private puzzle data, with the extension of their plaintext files.
+fn private_folders() -> [(PathBuf, &'static str); 2] {
+    [
+        (config().data_dir.join("inputs"), "txt"),
+        (config().data_dir.join("puzzles"), "md"),
+    ]
+}
+
+fn files_with_extension(folder: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<_> = fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn load_key_or_exit() -> chacha20poly1305::Key {
+    match sealed::load_key() {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("Failed to load key: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn encrypt() {
+    let key = load_key_or_exit();
+    let mut count = 0;
+
+    for (folder, extension) in private_folders() {
+        for path in files_with_extension(&folder, extension) {
+            let Ok(plaintext) = fs::read(&path) else {
+                continue;
+            };
+            if plaintext.is_empty() {
+                continue;
+            }
+
+            // keep existing sealed files that are still up to date, so that git sees no change.
+            let sealed_path = sealed::sealed_path(&path);
+            let is_current = fs::read(&sealed_path)
+                .ok()
+                .and_then(|s| sealed::open(&key, &s).ok())
+                .is_some_and(|existing| existing == plaintext);
+            if is_current {
+                continue;
+            }
+
+            if let Err(e) = fs::write(&sealed_path, sealed::seal(&key, &plaintext)) {
+                eprintln!("Failed to write \"{}\": {e}", sealed_path.display());
+                process::exit(1);
+            }
+            println!("Sealed \"{}\".", path.display());
+            count += 1;
+        }
+    }
+
+    println!("---");
+    println!("🎄 Sealed {count} file(s).");
+}
+
+fn decrypt() {
+    let key = load_key_or_exit();
+    let mut count = 0;
+
+    for (folder, _) in private_folders() {
+        for sealed_path in files_with_extension(&folder, sealed::SEALED_EXTENSION) {
+            let path = sealed_path.with_extension("");
+            let plaintext = match fs::read(&sealed_path)
+                .map_err(sealed::Error::IO)
+                .and_then(|s| sealed::open(&key, &s))
+            {
+                Ok(plaintext) => plaintext,
+                Err(e) => {
+                    eprintln!("Failed to open \"{}\": {e}", sealed_path.display());
+                    process::exit(1);
+                }
+            };
+
+            if fs::read(&path).is_ok_and(|existing| existing == plaintext) {
+                continue;
+            }
+
+            if let Err(e) = fs::write(&path, plaintext) {
+                eprintln!("Failed to write \"{}\": {e}", path.display());
+                process::exit(1);
+            }
+            println!("Decrypted \"{}\".", path.display());
+            count += 1;
+        }
+    }
+
+    println!("---");
+    println!("🎄 Decrypted {count} file(s).");
+}
+
+fn check() {
+    let folders: Vec<_> = private_folders().into_iter().map(|(f, _)| f).collect();
+
+    let output = match Command::new("git")
+        .arg("ls-files")
+        .arg("--")
+        .args(&folders)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => {
+            eprintln!("Failed to list tracked files, is this a git repository?");
+            process::exit(1);
+        }
+    };
+
+    let tracked_plaintext: Vec<_> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "txt" || ext == "md")
+        })
+        .collect();
+
+    let mut unsealed = 0;
+    for (folder, extension) in private_folders() {
+        for path in files_with_extension(&folder, extension) {
+            if fs::metadata(&path).is_ok_and(|m| m.len() > 0)
+                && !sealed::sealed_path(&path).exists()
+            {
+                unsealed += 1;
+            }
+        }
+    }
+    if unsealed > 0 {
+        println!(
+            "{unsealed} file(s) have no sealed copy yet. Run `cargo inputs encrypt` to seal them."
+        );
+    }
+
+    if tracked_plaintext.is_empty() {
+        println!("🎄 No plaintext inputs or puzzles are tracked by git.");
+        return;
+    }
+
+    eprintln!("Warning: the following plaintext files are tracked by git:");
+    for path in &tracked_plaintext {
+        eprintln!("  {}", path.display());
+    }
+    eprintln!(
+        "Seal them with `cargo inputs encrypt`, then untrack them with `git rm --cached <file>`."
+    );
+    process::exit(1);
+}
+
+fn keygen() {
+    let path = &config().key_file;
+    if path.exists() {
+        eprintln!("Key file \"{}\" already exists.", path.display());
+        process::exit(1);
+    }
+
+    if let Err(e) = fs::write(path, sealed::generate_key() + "\n") {
+        eprintln!("Failed to write key file: {e}");
+        process::exit(1);
+    }
+
+    println!("Created key file \"{}\".", path.display());
+    println!("---");
+    println!(
+        "🎄 Share this key with your team through a private channel. Never commit it, or set `{}` instead.",
+        sealed::KEY_ENV
+    );
+}
+
+pub fn handle(action: Action) {
+    match action {
+        Action::Encrypt => encrypt(),
+        Action::Decrypt => decrypt(),
+        Action::Check => check(),
+        Action::Keygen => keygen(),
+    }
+}
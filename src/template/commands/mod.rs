@@ -1,6 +1,9 @@
 pub mod all;
+pub mod dashboard;
 pub mod download;
+pub mod inputs;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
 pub mod time;
@@ -1,8 +1,43 @@
-use std::process;
+use std::{
+    env,
+    io::{stdout, IsTerminal, Write},
+    process::{self, Command, Stdio},
+};
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, config::config, markdown, sealed, Day};
+
+/// Writes `text` through `$PAGER` (or `less -R`) if stdout is a terminal, directly otherwise.
+fn page(text: &str) {
+    if stdout().is_terminal() {
+        let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".into());
+        let mut parts = pager.split_whitespace();
+
+        if let Some(program) = parts.next() {
+            if let Ok(mut child) = Command::new(program)
+                .args(parts)
+                .stdin(Stdio::piped())
+                .spawn()
+            {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // the pager closing its input early is not an error.
+                    let _ = stdin.write_all(text.as_bytes());
+                }
+                let _ = child.wait();
+                return;
+            }
+        }
+    }
+
+    print!("{text}");
+}
 
 pub fn handle(day: Day) {
+    // prefer the offline copy written by `cargo download`.
+    if let Ok(puzzle) = sealed::read_to_string(&config().puzzle_path(day)) {
+        page(&markdown::render(&puzzle, markdown::terminal_width()));
+        return;
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
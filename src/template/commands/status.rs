@@ -0,0 +1,86 @@
+use std::io::{stdout, Write};
+
+use crate::template::status::{all_statuses, DayStatus, Verification};
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+fn check(value: bool) -> &'static str {
+    if value {
+        "✔"
+    } else {
+        "✖"
+    }
+}
+
+fn answers_cell(status: &DayStatus) -> String {
+    match (status.answers.len(), status.verified) {
+        (0, _) => "-".into(),
+        (n, None) => format!("{n} recorded"),
+        (n, Some(Verification::Correct)) => format!("{n} ✔"),
+        (n, Some(Verification::Incorrect)) => format!("{n} ✖"),
+    }
+}
+
+fn timing_cell(status: &DayStatus) -> String {
+    status.timing.as_ref().map_or_else(
+        || "-".into(),
+        |timing| {
+            format!(
+                "{} / {}",
+                timing.part_1.as_deref().unwrap_or("-"),
+                timing.part_2.as_deref().unwrap_or("-")
+            )
+        },
+    )
+}
+
+fn format_row(status: &DayStatus) -> String {
+    let examples = if status.examples == 0 {
+        "-".into()
+    } else {
+        status.examples.to_string()
+    };
+    let tests = status.tests.map_or("-", check);
+
+    format!(
+        "{:<5}{:<10}{:<7}{:<10}{:<7}{:<12}{}",
+        status.day.to_string(),
+        check(status.scaffolded),
+        check(status.has_input),
+        examples,
+        tests,
+        answers_cell(status),
+        timing_cell(status)
+    )
+}
+
+pub fn handle(run_tests: bool, verify: bool) {
+    let mut statuses = all_statuses();
+
+    if run_tests || verify {
+        let mut stdout = stdout();
+        for status in statuses.iter_mut().filter(|s| s.scaffolded) {
+            print!("\r{ANSI_ITALIC}checking day {}...{ANSI_RESET}", status.day);
+            let _ = stdout.flush();
+            if run_tests {
+                status.run_tests();
+            }
+            if verify {
+                status.verify();
+            }
+        }
+        print!("\r                        \r");
+    }
+
+    println!(
+        "{ANSI_BOLD}{:<5}{:<10}{:<7}{:<10}{:<7}{:<12}Timing{ANSI_RESET}",
+        "Day", "Solution", "Input", "Examples", "Tests", "Answers"
+    );
+    for status in &statuses {
+        println!("{}", format_row(status));
+    }
+
+    let solved = statuses.iter().filter(|s| s.scaffolded).count();
+    let timed = statuses.iter().filter(|s| s.timing.is_some()).count();
+    println!();
+    println!("{solved}/25 days scaffolded, {timed}/25 days benchmarked.");
+}
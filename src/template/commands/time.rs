@@ -1,10 +1,16 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, process};
 
 use crate::template::run_multi::run_multi;
-use crate::template::timings::Timings;
+use crate::template::timings::{Timings, TimingsHistory};
 use crate::template::{all_days, readme_benchmarks, Day};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+/// Benches the solutions. `args` are passed on to them, e.g. the flag of a day's tool.
+pub fn handle(day: Option<Day>, run_all: bool, store: bool, args: &[String]) {
+    if store && !args.is_empty() {
+        eprintln!("Timings of solutions run with {args:?} cannot be stored.");
+        process::exit(1);
+    }
+
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -21,12 +27,16 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, true, args).unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
         merged_timings.store_file().unwrap();
 
+        let mut history = TimingsHistory::read_from_file();
+        history.push(timings);
+        history.store_file().unwrap();
+
         println!();
         match readme_benchmarks::update(merged_timings) {
             Ok(()) => {
@@ -0,0 +1,372 @@
+/// Project settings, read from `aoc.toml` at the project root.
+///
+/// Every path the template touches is derived from the values in here, so commands
+/// should never build `data/...` or `src/bin/...` paths on their own.
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use crate::template::Day;
+
+static CONFIG_FILE_PATH: &str = "aoc.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Controls what happens when a solution is run with `--submit <part>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubmitPolicy {
+    /// Submit right away.
+    #[default]
+    Allow,
+    /// Ask for confirmation on stdin before submitting.
+    Confirm,
+    /// Never submit, even if `--submit` is passed.
+    Deny,
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub year: Option<u16>,
+    pub data_dir: PathBuf,
+    pub bin_dir: PathBuf,
+    pub readme: PathBuf,
+    pub template: Option<PathBuf>,
+    pub bench_budget: Duration,
+    pub submit: SubmitPolicy,
+    pub key_file: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            data_dir: "data".into(),
+            bin_dir: "src/bin".into(),
+            readme: "README.md".into(),
+            template: None,
+            bench_budget: Duration::from_secs(1),
+            submit: SubmitPolicy::default(),
+            key_file: ".aoc-inputs-key".into(),
+        }
+    }
+}
+
+/// Returns the project configuration, loading it on first use.
+///
+/// A missing `aoc.toml` yields the defaults. A malformed one terminates the process,
+/// since none of the commands can do anything sensible without knowing where files live.
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(|| match Config::load(Path::new(CONFIG_FILE_PATH)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load \"{CONFIG_FILE_PATH}\": {e}");
+            process::exit(1);
+        }
+    })
+}
+
+impl Config {
+    /// Reads the config from `path`. If not present, returns the default config.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::try_from(contents.as_str()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::IO(e)),
+        }
+    }
+
+    /// The year to solve. Falls back to the `AOC_YEAR` environment variable.
+    pub fn year(&self) -> Option<u16> {
+        self.year
+            .or_else(|| env::var("AOC_YEAR").ok().and_then(|x| x.parse().ok()))
+    }
+
+    /// Path to a file in one of the data folders (`inputs`, `examples`, ...).
+    pub fn data_path(&self, folder: &str, file_name: &str) -> PathBuf {
+        self.data_dir.join(folder).join(file_name)
+    }
+
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.data_path("inputs", &format!("{day}.txt"))
+    }
+
+    pub fn example_path(&self, day: Day) -> PathBuf {
+        self.data_path("examples", &format!("{day}.txt"))
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.data_path("puzzles", &format!("{day}.md"))
+    }
+
+    /// Recorded answers for a day, one line per part.
+    pub fn answers_path(&self, day: Day) -> PathBuf {
+        self.data_path("answers", &format!("{day}.txt"))
+    }
+
+    pub fn timings_path(&self) -> PathBuf {
+        self.data_dir.join("timings.json")
+    }
+
+    pub fn timings_history_path(&self) -> PathBuf {
+        self.data_dir.join("timings-history.json")
+    }
+
+    pub fn bin_path(&self, day: Day) -> PathBuf {
+        self.bin_dir.join(format!("{day}.rs"))
+    }
+
+    /// Link to a solution binary as it appears in the readme, e.g. `./src/bin/01.rs`.
+    pub fn bin_link(&self, day: Day) -> String {
+        format!("./{}/{day}.rs", self.bin_dir.display())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(msg) => f.write_str(msg),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A scalar value in the (small) subset of TOML understood by the config parser.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    String(String),
+    Integer(i64),
+}
+
+impl TryFrom<&str> for Config {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut table = parse_table(value)?;
+        let mut config = Config::default();
+
+        if let Some(year) = table.remove("year") {
+            let year = expect_integer("year", year)?;
+            config.year = Some(
+                u16::try_from(year).map_err(|_| Error::Parser(format!("invalid year {year}.")))?,
+            );
+        }
+        if let Some(dir) = table.remove("data_dir") {
+            config.data_dir = expect_string("data_dir", dir)?.into();
+        }
+        if let Some(dir) = table.remove("bin_dir") {
+            config.bin_dir = expect_string("bin_dir", dir)?.into();
+        }
+        if let Some(readme) = table.remove("readme") {
+            config.readme = expect_string("readme", readme)?.into();
+        }
+        if let Some(template) = table.remove("template") {
+            config.template = Some(expect_string("template", template)?.into());
+        }
+        if let Some(budget) = table.remove("bench.budget_ms") {
+            let budget = expect_integer("bench.budget_ms", budget)?;
+            let budget = u64::try_from(budget)
+                .map_err(|_| Error::Parser("expected `bench.budget_ms` to be positive.".into()))?;
+            config.bench_budget = Duration::from_millis(budget);
+        }
+        if let Some(policy) = table.remove("submit.policy") {
+            config.submit = match expect_string("submit.policy", policy)?.as_str() {
+                "allow" => SubmitPolicy::Allow,
+                "confirm" => SubmitPolicy::Confirm,
+                "deny" => SubmitPolicy::Deny,
+                x => {
+                    return Err(Error::Parser(format!(
+                        "expected `submit.policy` to be one of \"allow\", \"confirm\" or \"deny\", got \"{x}\"."
+                    )))
+                }
+            };
+        }
+
+        if let Some(key_file) = table.remove("inputs.key_file") {
+            config.key_file = expect_string("inputs.key_file", key_file)?.into();
+        }
+
+        if let Some(key) = table.keys().next() {
+            return Err(Error::Parser(format!("unknown key `{key}`.")));
+        }
+
+        Ok(config)
+    }
+}
+
+fn expect_string(key: &str, value: Value) -> Result<String, Error> {
+    match value {
+        Value::String(s) => Ok(s),
+        _ => Err(Error::Parser(format!("expected `{key}` to be a string."))),
+    }
+}
+
+fn expect_integer(key: &str, value: Value) -> Result<i64, Error> {
+    match value {
+        Value::Integer(n) => Ok(n),
+        _ => Err(Error::Parser(format!("expected `{key}` to be an integer."))),
+    }
+}
+
+/// Parses `key = value` pairs and `[section]` headers into a flat map of dotted keys.
+fn parse_table(s: &str) -> Result<HashMap<String, Value>, Error> {
+    let mut table = HashMap::new();
+    let mut section = String::new();
+
+    for (i, line) in s.lines().enumerate() {
+        let line_no = i + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| Error::Parser(format!("line {line_no}: unterminated section.")))?;
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| Error::Parser(format!("line {line_no}: expected `key = value`.")))?;
+
+        let key = key.trim();
+        let key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{section}.{key}")
+        };
+
+        let value = parse_value(value.trim())
+            .ok_or_else(|| Error::Parser(format!("line {line_no}: invalid value for `{key}`.")))?;
+
+        if table.insert(key.clone(), value).is_some() {
+            return Err(Error::Parser(format!(
+                "line {line_no}: duplicate key `{key}`."
+            )));
+        }
+    }
+
+    Ok(table)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(s: &str) -> Option<Value> {
+    match s {
+        s if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') => {
+            Some(Value::String(s[1..s.len() - 1].to_string()))
+        }
+        s => s.replace('_', "").parse().ok().map(Value::Integer),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Config, SubmitPolicy};
+    use crate::day;
+
+    #[test]
+    fn handles_empty_config() {
+        let config = Config::try_from("").unwrap();
+        assert_eq!(config.data_dir.to_str(), Some("data"));
+        assert_eq!(config.readme.to_str(), Some("README.md"));
+        assert_eq!(config.bench_budget, Duration::from_secs(1));
+        assert_eq!(config.submit, SubmitPolicy::Allow);
+    }
+
+    #[test]
+    fn handles_full_config() {
+        let config = Config::try_from(
+            r#"
+            # comment
+            year = 2023
+            data_dir = "puzzle-data" # trailing comment
+            readme = "docs/README.md"
+            template = "src/my-template.txt"
+
+            [bench]
+            budget_ms = 2_500
+
+            [submit]
+            policy = "confirm"
+
+            [inputs]
+            key_file = "../aoc.key"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.readme.to_str(), Some("docs/README.md"));
+        assert_eq!(config.bench_budget, Duration::from_millis(2500));
+        assert_eq!(config.submit, SubmitPolicy::Confirm);
+        assert_eq!(config.key_file.to_str(), Some("../aoc.key"));
+        assert_eq!(
+            config.input_path(day!(3)).to_str(),
+            Some("puzzle-data/inputs/03.txt")
+        );
+    }
+
+    #[test]
+    fn builds_bin_paths() {
+        let config = Config::default();
+        assert_eq!(config.bin_path(day!(1)).to_str(), Some("src/bin/01.rs"));
+        assert_eq!(config.bin_link(day!(1)), "./src/bin/01.rs");
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_unknown_keys() {
+        Config::try_from("datadir = \"data\"").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_submit_policy() {
+        Config::try_from("[submit]\npolicy = \"sometimes\"").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_mistyped_values() {
+        Config::try_from("year = \"2024\"").unwrap();
+    }
+}
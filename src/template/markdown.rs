@@ -0,0 +1,413 @@
+/// Renders the puzzle markdown written by aoc-cli for display in a terminal.
+///
+/// Supports the subset aoc-cli produces: ATX and setext headings, paragraphs, emphasis,
+/// inline code, links, fenced and indented code blocks and (ordered) lists.
+use std::{
+    env,
+    fs::File,
+    process::{Command, Stdio},
+};
+
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const DEFAULT_WIDTH: usize = 80;
+const MAX_WIDTH: usize = 100;
+
+/// Width to wrap text at, capped to keep lines readable. `COLUMNS` overrides the width of the
+/// terminal, which is asked with `stty size` since shells rarely export `COLUMNS`.
+pub fn terminal_width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .or_else(tty_columns)
+        .filter(|&c: &usize| c >= 20)
+        .unwrap_or(DEFAULT_WIDTH)
+        .min(MAX_WIDTH)
+}
+
+/// Columns of the controlling terminal, if there is one. Goes through `/dev/tty` so it works
+/// while stdout is piped.
+fn tty_columns() -> Option<usize> {
+    let tty = File::open("/dev/tty").ok()?;
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::from(tty))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_stty_size(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The columns from the `rows columns` printed by `stty size`.
+fn parse_stty_size(output: &str) -> Option<usize> {
+    let mut numbers = output.split_whitespace().map(|n| n.parse::<usize>().ok());
+    let (_rows, columns) = (numbers.next()??, numbers.next()??);
+    (columns > 0).then_some(columns)
+}
+
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    Code(Vec<String>),
+    ListItem { marker: String, text: String },
+}
+
+/// Renders `markdown` to styled text, wrapped at `width` columns.
+pub fn render(markdown: &str, width: usize) -> String {
+    let blocks = parse_blocks(markdown);
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut previous_was_item = false;
+
+    for block in blocks {
+        let is_item = matches!(block, Block::ListItem { .. });
+        if !lines.is_empty() && (!is_item || !previous_was_item) {
+            lines.push(String::new());
+        }
+        previous_was_item = is_item;
+
+        match block {
+            Block::Heading(text) => {
+                let text = render_inline(&text);
+                lines.push(format!("{ANSI_BOLD}{text}{ANSI_RESET}"));
+            }
+            Block::Paragraph(text) => {
+                lines.extend(wrap(&render_inline(&text), width, "", ""));
+            }
+            Block::Code(code) => {
+                lines.extend(code.into_iter().map(|line| format!("    {line}")));
+            }
+            Block::ListItem { marker, text } => {
+                let first = format!("  {marker} ");
+                let rest = " ".repeat(first.chars().count());
+                lines.extend(wrap(&render_inline(&text), width, &first, &rest));
+            }
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim().starts_with("```") {
+                code.push(lines[i].to_string());
+                i += 1;
+            }
+            blocks.push(Block::Code(code));
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if paragraph.is_empty() && line.starts_with("    ") {
+            let mut code = Vec::new();
+            while i < lines.len() && (lines[i].starts_with("    ") || lines[i].trim().is_empty()) {
+                code.push(lines[i].get(4..).unwrap_or("").to_string());
+                i += 1;
+            }
+            while code.last().is_some_and(String::is_empty) {
+                code.pop();
+            }
+            blocks.push(Block::Code(code));
+            continue;
+        } else if let Some(heading) = atx_heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(heading.to_string()));
+        } else if paragraph.is_empty() && lines.get(i + 1).is_some_and(|l| is_setext_underline(l)) {
+            blocks.push(Block::Heading(trimmed.to_string()));
+            i += 1;
+        } else if let Some((marker, text)) = list_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            let mut text = text.to_string();
+            // continuation lines of a list item are indented.
+            while lines.get(i + 1).is_some_and(|l| {
+                l.starts_with("  ") && !l.trim().is_empty() && list_item(l.trim()).is_none()
+            }) {
+                i += 1;
+                text.push(' ');
+                text.push_str(lines[i].trim());
+            }
+            blocks.push(Block::ListItem { marker, text });
+        } else {
+            paragraph.push(trimmed);
+        }
+
+        i += 1;
+    }
+
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn atx_heading(line: &str) -> Option<&str> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    if hashes == 0 || hashes > 6 {
+        return None;
+    }
+    line[hashes..]
+        .strip_prefix(' ')
+        .map(|text| text.trim_end_matches('#').trim())
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '='))
+}
+
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some(("•".into(), text.trim_start()));
+        }
+    }
+
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(text) = line[digits..].strip_prefix(". ") {
+            return Some((line[..=digits].to_string(), text.trim_start()));
+        }
+    }
+
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Default)]
+struct Styles {
+    bold: bool,
+    italic: bool,
+}
+
+impl Styles {
+    /// Resets all styling, then re-applies the active styles.
+    fn apply(&self, out: &mut String) {
+        out.push_str(ANSI_RESET);
+        if self.bold {
+            out.push_str(ANSI_BOLD);
+        }
+        if self.italic {
+            out.push_str(ANSI_ITALIC);
+        }
+    }
+}
+
+/// Replaces inline markdown with ANSI styling: `**strong**` is bold, `*em*` and `_em_` are italic.
+fn render_inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut styles = Styles::default();
+
+    let is_word = |i: Option<usize>| {
+        i.and_then(|i| chars.get(i))
+            .is_some_and(|c| c.is_alphanumeric())
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if chars.get(i + 1).is_some_and(char::is_ascii_punctuation) => {
+                out.push(chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            '`' => {
+                if let Some(len) = chars[i + 1..].iter().position(|&c| c == '`') {
+                    out.extend(&chars[i + 1..i + 1 + len]);
+                    i += len + 2;
+                    continue;
+                }
+                out.push(c);
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                styles.bold = !styles.bold;
+                styles.apply(&mut out);
+                i += 2;
+                continue;
+            }
+            '*' | '_' => {
+                let opens = !styles.italic
+                    && chars.get(i + 1).is_some_and(|c| !c.is_whitespace())
+                    && (c == '*' || !is_word(i.checked_sub(1)));
+                let closes = styles.italic
+                    && i > 0
+                    && !chars[i - 1].is_whitespace()
+                    && (c == '*' || !is_word(Some(i + 1)));
+                if opens || closes {
+                    styles.italic = !styles.italic;
+                    styles.apply(&mut out);
+                } else {
+                    out.push(c);
+                }
+            }
+            '[' => {
+                if let Some((label, len)) = link(&chars[i..]) {
+                    out.push_str(&label);
+                    i += len;
+                    continue;
+                }
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+        i += 1;
+    }
+
+    if styles.bold || styles.italic {
+        out.push_str(ANSI_RESET);
+    }
+    out
+}
+
+/// Parses a `[label](url)` link at the start of `chars`, returning the label and the link length.
+fn link(chars: &[char]) -> Option<(String, usize)> {
+    let label_end = chars.iter().position(|&c| c == ']')?;
+    if chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    let url_len = chars[label_end + 2..].iter().position(|&c| c == ')')?;
+    let label: String = chars[1..label_end].iter().collect();
+    Some((render_inline(&label), label_end + url_len + 3))
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in s.chars() {
+        if in_escape {
+            in_escape = !c.is_ascii_alphabetic();
+        } else if c == '\x1b' {
+            in_escape = true;
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Word-wraps styled text. Words longer than a line are kept intact.
+fn wrap(text: &str, width: usize, first_prefix: &str, rest_prefix: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = first_prefix.to_string();
+    let mut line_width = visible_width(first_prefix);
+    let mut line_is_empty = true;
+
+    for word in text.split(' ').filter(|w| !w.is_empty()) {
+        let word_width = visible_width(word);
+        if !line_is_empty && line_width + 1 + word_width > width {
+            lines.push(line);
+            line = rest_prefix.to_string();
+            line_width = visible_width(rest_prefix);
+            line_is_empty = true;
+        }
+        if !line_is_empty {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(word);
+        line_width += word_width;
+        line_is_empty = false;
+    }
+
+    lines.push(line);
+    lines
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_stty_size, render, render_inline, visible_width, wrap};
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    #[test]
+    fn renders_emphasis() {
+        assert_eq!(
+            render_inline("a *b* c"),
+            format!("a {ANSI_RESET}{ANSI_ITALIC}b{ANSI_RESET} c")
+        );
+        assert_eq!(
+            render_inline("**b**"),
+            format!("{ANSI_RESET}{ANSI_BOLD}b{ANSI_RESET}")
+        );
+    }
+
+    #[test]
+    fn parses_stty_size() {
+        assert_eq!(parse_stty_size("50 132\n"), Some(132));
+        assert_eq!(parse_stty_size("0 0\n"), None);
+        assert_eq!(parse_stty_size(""), None);
+    }
+
+    #[test]
+    fn keeps_literals() {
+        assert_eq!(render_inline("snake_case_name"), "snake_case_name");
+        assert_eq!(render_inline("2 * 3 = 6"), "2 * 3 = 6");
+        assert_eq!(render_inline("\\--- Day 1 ---"), "--- Day 1 ---");
+        assert_eq!(render_inline("`*x*`"), "*x*");
+        assert_eq!(render_inline("[site](https://example.com)"), "site");
+    }
+
+    #[test]
+    fn wraps_by_visible_width() {
+        let text = format!("{ANSI_BOLD}aaaa{ANSI_RESET} bbbb cccc");
+        let lines = wrap(&text, 9, "", "");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(visible_width(&lines[0]), 9);
+        assert_eq!(lines[1], "cccc");
+    }
+
+    #[test]
+    fn renders_blocks() {
+        let markdown = "\\--- Day 1: Test ---\n----------\n\nSome text\ncontinues here.\n\n```\nlet x = 1;\n```\n\n* one\n* two\n\n1. first";
+        let rendered = render(markdown, 80);
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                format!("{ANSI_BOLD}--- Day 1: Test ---{ANSI_RESET}").as_str(),
+                "",
+                "Some text continues here.",
+                "",
+                "    let x = 1;",
+                "",
+                "  • one",
+                "  • two",
+                "  1. first",
+            ]
+        );
+    }
+
+    #[test]
+    fn indents_wrapped_list_items() {
+        let rendered = render("- aaa bbb ccc", 10);
+        assert_eq!(rendered, "  • aaa\n    bbb\n    ccc\n");
+    }
+}
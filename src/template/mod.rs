@@ -1,4 +1,6 @@
-use std::{env, fs};
+use std::env;
+
+use config::config;
 
 pub mod aoc_cli;
 pub mod commands;
@@ -6,9 +8,13 @@ pub mod runner;
 
 pub use day::*;
 
+mod config;
 mod day;
+mod markdown;
 mod readme_benchmarks;
 mod run_multi;
+mod sealed;
+mod status;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
@@ -19,8 +25,8 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
+    let filepath = cwd.join(config().data_path(folder, &format!("{day}.txt")));
+    let f = sealed::read_to_string(&filepath);
     f.expect("could not open input file")
 }
 
@@ -28,17 +34,25 @@ pub fn read_file(folder: &str, day: Day) -> String {
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
+    let filepath = cwd.join(config().data_path(folder, &format!("{day}-{part}.txt")));
+    let f = sealed::read_to_string(&filepath);
     f.expect("could not open input file")
 }
 
+/// The argument following `flag` in the arguments of the binary, e.g. `out/` for `--frames out/`.
+/// Lets a day's tool take options.
+#[must_use]
+pub fn arg_value(flag: &str) -> Option<String> {
+    env::args().skip_while(|arg| arg != flag).nth(1)
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Tools for a day can be listed after a `;`, e.g. `solution!(17; "--debug" => debug)`. When the
+/// binary is started with one of the flags, e.g. `cargo solve 17 --debug`, the tool is called with
+/// the input instead of running the parts.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,8 +64,11 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr; $( $flag:literal => $tool:expr ),+ $(,)?) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; $( $flag => $tool ),+);
+    };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )* $(; $( $flag:literal => $tool:expr ),+ )?) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -62,6 +79,11 @@ macro_rules! solution {
         fn main() {
             use $crate::template::runner::*;
             let input = $crate::template::read_file("inputs", DAY);
+            $($(
+                if std::env::args().any(|arg| arg == $flag) {
+                    return $tool(&input);
+                }
+            )+)?
             $( run_part($func, &input, DAY, $part); )*
         }
     };
@@ -2,8 +2,8 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::config::config;
 use crate::template::timings::Timings;
-use crate::template::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -25,11 +25,6 @@ pub struct TablePosition {
     pos_end: usize,
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     let matches: Vec<_> = readme.match_indices(MARKER).collect();
 
@@ -64,7 +59,7 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
     ];
 
     for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
+        let path = config().bin_link(timing.day);
         lines.push(format!(
             "| [Day {}]({}) | `{}` | `{}` |",
             timing.day.into_inner(),
@@ -89,7 +84,7 @@ fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result
 }
 
 pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+    let path = &config().readme;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
     update_content(&mut readme, timings, total_millis)?;
@@ -7,7 +7,42 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Receives the output of solution runs, line by line.
+pub trait OutputSink: Sync {
+    fn stdout(&self, line: &str);
+    fn stderr(&self, line: &str);
+}
+
+/// Forwards output to the terminal.
+pub struct Terminal;
+
+impl OutputSink for Terminal {
+    fn stdout(&self, line: &str) {
+        println!("{line}");
+    }
+
+    fn stderr(&self, line: &str) {
+        eprintln!("{line}");
+    }
+}
+
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    args: &[String],
+) -> Option<Timings> {
+    run_multi_to(days_to_run, is_release, is_timed, args, &Terminal)
+}
+
+/// Same as [`run_multi`], but sends all output to `sink` instead of the terminal.
+pub fn run_multi_to(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    args: &[String],
+    sink: &impl OutputSink,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
@@ -17,17 +52,18 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         .filter(|day| days_to_run.contains(day))
         .for_each(|day| {
             if need_space {
-                println!();
+                sink.stdout("");
             }
             need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+            sink.stdout(&format!("{ANSI_BOLD}Day {day}{ANSI_RESET}"));
+            sink.stdout("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output =
+                child_commands::run_solution(day, is_timed, is_release, args, sink).unwrap();
 
             if output.is_empty() {
-                println!("Not solved.");
+                sink.stdout("Not solved.");
             } else {
                 let val = child_commands::parse_exec_time(&output, day);
                 timings.push(val);
@@ -37,9 +73,10 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
     if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
-        println!(
-            "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
-        );
+        sink.stdout("");
+        sink.stdout(&format!(
+            "{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+        ));
         Some(timings)
     } else {
         None
@@ -59,27 +96,27 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{Error, OutputSink};
+    use crate::template::{config::config, Day};
     use std::{
         io::{BufRead, BufReader},
-        path::Path,
         process::{Command, Stdio},
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, passing `solution_args` on to it.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        solution_args: &[String],
+        sink: &impl OutputSink,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
+        if !config().bin_path(day).exists() {
             return Ok(vec![]);
         }
 
@@ -90,12 +127,17 @@ pub mod child_commands {
             args.push("--release");
         }
 
+        if is_timed || !solution_args.is_empty() {
+            args.push("--");
+        }
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
+        args.extend(solution_args.iter().map(String::as_str));
+
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
@@ -110,24 +152,45 @@ pub mod child_commands {
 
         let mut output = vec![];
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                stderr.lines().for_each(|line| {
+                    sink.stderr(&line.unwrap());
+                });
             });
-        });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
-        }
+            for line in stdout.lines() {
+                let line = line.unwrap();
+                sink.stdout(&line);
+                output.push(line);
+            }
+        });
 
-        thread.join().unwrap();
         cmd.wait()?;
 
         Ok(output)
     }
 
+    /// Run the solution bin for a given day without forwarding its output.
+    pub fn capture_solution(day: Day, is_release: bool) -> Result<Vec<String>, Error> {
+        let day_padded = day.to_string();
+        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+
+        if is_release {
+            args.push("--release");
+        }
+
+        let output = Command::new("cargo")
+            .args(&args)
+            .stderr(Stdio::null())
+            .output()?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
@@ -174,7 +237,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -1,30 +1,70 @@
 /// Encapsulates code that interacts with solution functions.
 use std::fmt::Display;
 use std::hint::black_box;
-use std::io::{stdout, Write};
+use std::io::{stdin, stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::config::{config, SubmitPolicy};
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// The return type of a solution part: `Option<T>`, or `Result<T, E>` for parts that can fail,
+/// e.g. with a [`crate::parse::ParseError`].
+pub trait PartResult {
+    type Answer: Display;
+
+    fn into_result(self) -> Result<Option<Self::Answer>, String>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Answer = T;
+
+    fn into_result(self) -> Result<Option<T>, String> {
+        Ok(self)
+    }
+}
+
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    type Answer = T;
+
+    fn into_result(self) -> Result<Option<T>, String> {
+        self.map(Some).map_err(|err| err.to_string())
+    }
+}
+
+pub fn run_part<I: Clone, R: PartResult>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = run_timed(
+        |input| func(input).into_result(),
+        input,
+        |result| print_result(answer(result), &part_str, ""),
+    );
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(
+        answer(&result),
+        &part_str,
+        &format_duration(&duration, samples),
+    );
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    match result {
+        Ok(Some(result)) => {
+            submit_result(result, day, part);
+        }
+        Ok(None) => {}
+        Err(err) => eprintln!("{part_str} failed: {err}"),
     }
 }
 
+fn answer<T>(result: &Result<Option<T>, String>) -> Option<&T> {
+    result.as_ref().ok().and_then(Option::as_ref)
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  2. in release, the function is benched (approx. the `bench.budget_ms` of execution time or 10 samples, whatever take longer.)
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
@@ -59,7 +99,7 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     let _ = stdout.flush();
 
     let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+        (config().bench_budget.as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -94,7 +134,7 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: Option<&T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -132,6 +172,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
+///  3. the `submit.policy` in `aoc.toml` allows it.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
@@ -159,6 +200,20 @@ fn submit_result<T: Display>(
         return None;
     }
 
+    match config().submit {
+        SubmitPolicy::Allow => {}
+        SubmitPolicy::Confirm => {
+            if !confirm_submit(&result, part) {
+                println!("Skipped submitting part {part}.");
+                return None;
+            }
+        }
+        SubmitPolicy::Deny => {
+            eprintln!("Submitting is disabled by `submit.policy` in aoc.toml.");
+            return None;
+        }
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
@@ -167,3 +222,14 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+fn confirm_submit<T: Display>(result: &T, part: u8) -> bool {
+    print!("Submit {ANSI_BOLD}{result}{ANSI_RESET} for part {part}? [y/N] ");
+    let _ = stdout().flush();
+
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
@@ -0,0 +1,217 @@
+/// Encrypts puzzle inputs and descriptions at rest, so they can be committed without publishing them.
+///
+/// A sealed file sits next to its plaintext with an added `.sealed` extension (e.g. `01.txt.sealed`)
+/// and contains a magic header, a random nonce and the ChaCha20-Poly1305 ciphertext.
+use std::{
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+use crate::template::config::config;
+
+pub const SEALED_EXTENSION: &str = "sealed";
+pub const KEY_ENV: &str = "AOC_INPUTS_KEY";
+
+const MAGIC: &[u8] = b"AOCSEAL1";
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug)]
+pub enum Error {
+    MissingKey,
+    InvalidKey,
+    Corrupt,
+    Decrypt,
+    IO(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingKey => write!(
+                f,
+                "no key found. Set `{KEY_ENV}` or create \"{}\" with `cargo inputs keygen`.",
+                config().key_file.display()
+            ),
+            Error::InvalidKey => write!(f, "expected the key to be 64 hex characters."),
+            Error::Corrupt => write!(f, "not a sealed file."),
+            Error::Decrypt => write!(f, "could not decrypt, is the key correct?"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reads the key from the `AOC_INPUTS_KEY` environment variable or the configured key file.
+pub fn load_key() -> Result<Key, Error> {
+    let hex = match env::var(KEY_ENV) {
+        Ok(hex) => hex,
+        Err(_) => match fs::read_to_string(&config().key_file) {
+            Ok(hex) => hex,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(Error::MissingKey),
+            Err(e) => return Err(Error::IO(e)),
+        },
+    };
+    parse_key(hex.trim())
+}
+
+/// Generates a random key, hex-encoded.
+pub fn generate_key() -> String {
+    ChaCha20Poly1305::generate_key(&mut OsRng)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn parse_key(hex: &str) -> Result<Key, Error> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(Error::InvalidKey);
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| Error::InvalidKey)?;
+    Ok(*Key::from_slice(&bytes))
+}
+
+pub fn seal(key: &Key, plaintext: &[u8]) -> Vec<u8> {
+    let cipher = ChaCha20Poly1305::new(key);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    // NOTE: encryption only fails for plaintexts larger than 256GiB.
+    let ciphertext = cipher.encrypt(&nonce, plaintext).unwrap();
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    sealed
+}
+
+pub fn open(key: &Key, sealed: &[u8]) -> Result<Vec<u8>, Error> {
+    let rest = sealed.strip_prefix(MAGIC).ok_or(Error::Corrupt)?;
+    if rest.len() < NONCE_LEN {
+        return Err(Error::Corrupt);
+    }
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    ChaCha20Poly1305::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::Decrypt)
+}
+
+/// The sealed counterpart of a plaintext file, e.g. `01.txt` -> `01.txt.sealed`.
+pub fn sealed_path(path: &Path) -> PathBuf {
+    let mut sealed = path.as_os_str().to_owned();
+    sealed.push(".");
+    sealed.push(SEALED_EXTENSION);
+    PathBuf::from(sealed)
+}
+
+/// Reads a file to a string. If it is missing or empty, e.g. as created by `cargo scaffold`,
+/// decrypts its sealed counterpart instead, if there is one.
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    read_to_string_with(path, load_key)
+}
+
+fn read_to_string_with(
+    path: &Path,
+    load_key: impl FnOnce() -> Result<Key, Error>,
+) -> io::Result<String> {
+    let plaintext = fs::read_to_string(path);
+    let missing = match &plaintext {
+        Ok(text) => text.is_empty(),
+        Err(e) => e.kind() == io::ErrorKind::NotFound,
+    };
+    if !missing {
+        return plaintext;
+    }
+    let Ok(sealed) = fs::read(sealed_path(path)) else {
+        return plaintext;
+    };
+    let plaintext = load_key()
+        .and_then(|key| open(&key, &sealed))
+        .map_err(io::Error::other)?;
+    String::from_utf8(plaintext).map_err(io::Error::other)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs, path::Path, process};
+
+    use super::{generate_key, open, parse_key, read_to_string_with, seal, sealed_path};
+
+    #[test]
+    fn roundtrips_sealed_data() {
+        let key = parse_key(&generate_key()).unwrap();
+        let sealed = seal(&key, b"1 2 3\n");
+        assert_eq!(open(&key, &sealed).unwrap(), b"1 2 3\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_wrong_key() {
+        let key = parse_key(&generate_key()).unwrap();
+        let other = parse_key(&generate_key()).unwrap();
+        open(&other, &seal(&key, b"secret")).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_tampered_data() {
+        let key = parse_key(&generate_key()).unwrap();
+        let mut sealed = seal(&key, b"secret");
+        *sealed.last_mut().unwrap() ^= 1;
+        open(&key, &sealed).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_keys() {
+        parse_key("abc").unwrap();
+    }
+
+    #[test]
+    fn appends_sealed_extension() {
+        assert_eq!(
+            sealed_path(Path::new("data/inputs/01.txt")),
+            Path::new("data/inputs/01.txt.sealed")
+        );
+    }
+
+    #[test]
+    fn prefers_sealed_copy_over_empty_file() {
+        let dir = env::temp_dir().join(format!("aoc-sealed-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("01.txt");
+        let key = parse_key(&generate_key()).unwrap();
+        fs::write(sealed_path(&path), seal(&key, b"1 2 3\n")).unwrap();
+
+        // missing, then empty as left by `cargo scaffold`, then filled in.
+        assert_eq!(read_to_string_with(&path, || Ok(key)).unwrap(), "1 2 3\n");
+        fs::write(&path, "").unwrap();
+        assert_eq!(read_to_string_with(&path, || Ok(key)).unwrap(), "1 2 3\n");
+        fs::write(&path, "4 5 6\n").unwrap();
+        assert_eq!(read_to_string_with(&path, || Ok(key)).unwrap(), "4 5 6\n");
+
+        // without a sealed copy, an empty file is read as it is.
+        fs::remove_file(sealed_path(&path)).unwrap();
+        fs::write(&path, "").unwrap();
+        assert_eq!(read_to_string_with(&path, || Ok(key)).unwrap(), "");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+/// Collects the progress of every day from the solution binaries, the data folder and the stored timings.
+use std::{
+    fs,
+    process::{Command, Stdio},
+};
+
+use crate::template::{
+    all_days,
+    config::config,
+    run_multi::child_commands,
+    sealed,
+    timings::{Timing, Timings},
+    Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// Outcome of comparing a solution's output against the recorded answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verification {
+    Correct,
+    Incorrect,
+}
+
+/// Represents the progress of a single day.
+#[derive(Clone, Debug)]
+pub struct DayStatus {
+    pub day: Day,
+    /// `src/bin/NN.rs` exists.
+    pub scaffolded: bool,
+    /// `data/inputs/NN.txt` exists and is not empty, or a sealed copy of it exists.
+    pub has_input: bool,
+    /// Number of non-empty example files, i.e. `NN.txt` or `NN-<part>.txt`.
+    pub examples: usize,
+    /// Whether the example tests pass. `None` if they were not run.
+    pub tests: Option<bool>,
+    /// Answers recorded in `data/answers/NN.txt`, one line per part.
+    pub answers: Vec<String>,
+    /// Whether the solution reproduces the recorded answers. `None` if not verified.
+    pub verified: Option<Verification>,
+    pub timing: Option<Timing>,
+}
+
+impl DayStatus {
+    /// Gathers the status of a day from the file system. Does not run any commands.
+    pub fn read(day: Day, timings: &Timings) -> Self {
+        let config = config();
+
+        let input_path = config.input_path(day);
+        let has_input = fs::metadata(&input_path).is_ok_and(|m| m.len() > 0)
+            || sealed::sealed_path(&input_path).exists();
+
+        let examples = fs::read_dir(config.data_dir.join("examples"))
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.metadata().is_ok_and(|m| m.len() > 0))
+                    .filter(|entry| is_example_for(&entry.file_name().to_string_lossy(), day))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        let answers = fs::read_to_string(config.answers_path(day))
+            .map(|s| {
+                s.lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            day,
+            scaffolded: config.bin_path(day).exists(),
+            has_input,
+            examples,
+            tests: None,
+            answers,
+            verified: None,
+            timing: timings.data.iter().find(|t| t.day == day).cloned(),
+        }
+    }
+
+    /// Runs the example tests of the day's binary.
+    pub fn run_tests(&mut self) {
+        if !self.scaffolded {
+            return;
+        }
+
+        let day = self.day.to_string();
+        let status = Command::new("cargo")
+            .args(["test", "--quiet", "--bin", &day])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+
+        self.tests = Some(status.is_ok_and(|s| s.success()));
+    }
+
+    /// Runs the solution against the real input and compares its output with the recorded answers.
+    pub fn verify(&mut self) {
+        if !self.scaffolded || !self.has_input || self.answers.is_empty() {
+            return;
+        }
+
+        let Ok(output) = child_commands::capture_solution(self.day, true) else {
+            self.verified = Some(Verification::Incorrect);
+            return;
+        };
+
+        let correct = self.answers.iter().enumerate().all(|(i, answer)| {
+            let part = format!("Part {}: ", i + 1);
+            output
+                .iter()
+                .filter_map(|line| line.strip_prefix(&part))
+                .filter_map(parse_answer)
+                .any(|result| result == answer)
+        });
+
+        self.verified = Some(if correct {
+            Verification::Correct
+        } else {
+            Verification::Incorrect
+        });
+    }
+}
+
+/// Reads the status of all days.
+pub fn all_statuses() -> Vec<DayStatus> {
+    let timings = Timings::read_from_file();
+    all_days()
+        .map(|day| DayStatus::read(day, &timings))
+        .collect()
+}
+
+fn is_example_for(file_name: &str, day: Day) -> bool {
+    let Some(stem) = file_name.strip_suffix(".txt") else {
+        return false;
+    };
+    let day = day.to_string();
+    stem == day
+        || stem
+            .strip_prefix(&day)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|part| part.parse::<u8>().is_ok())
+}
+
+/// Extracts the result from the remainder of a `Part N: ` line as printed by the runner.
+fn parse_answer(line: &str) -> Option<&str> {
+    line.strip_prefix(ANSI_BOLD)?.split(ANSI_RESET).next()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{is_example_for, parse_answer};
+    use crate::day;
+
+    #[test]
+    fn matches_example_files() {
+        assert!(is_example_for("03.txt", day!(3)));
+        assert!(is_example_for("03-2.txt", day!(3)));
+        assert!(!is_example_for("13.txt", day!(3)));
+        assert!(!is_example_for("03-x.txt", day!(3)));
+        assert!(!is_example_for("03.md", day!(3)));
+    }
+
+    #[test]
+    fn parses_answers_from_runner_output() {
+        assert_eq!(parse_answer("\x1b[1m4,6,3\x1b[0m (1.2ms)"), Some("4,6,3"));
+        assert_eq!(parse_answer("✖             "), None);
+    }
+}
@@ -1,9 +1,13 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Error,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
-
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+use crate::template::{config::config, Day};
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -25,13 +29,13 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(config().timings_path())?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        fs::read_to_string(config().timings_path())
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
@@ -51,7 +55,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|t| t.day);
         Timings { data }
     }
 
@@ -87,7 +91,14 @@ impl TryFrom<String> for Timings {
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        Timings::try_from(&json)
+    }
+}
+
+impl TryFrom<&JsonValue> for Timings {
+    type Error = String;
 
+    fn try_from(json: &JsonValue) -> Result<Self, Self::Error> {
         let json_data = json
             .get::<HashMap<String, JsonValue>>()
             .ok_or("expected JSON document to be an object.")?
@@ -177,6 +188,124 @@ impl TryFrom<&JsonValue> for Timing {
 
 /* -------------------------------------------------------------------------- */
 
+/// A set of timings stored at a point in time.
+#[derive(Clone, Debug)]
+pub struct TimingsSnapshot {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub timings: Timings,
+}
+
+/// Every set of timings stored with `cargo time --store`, oldest first.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct TimingsHistory {
+    pub runs: Vec<TimingsSnapshot>,
+}
+
+impl TimingsHistory {
+    /// Dehydrate history to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(config().timings_history_path())?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate history from a JSON file. If not present, returns an empty history.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(config().timings_history_path())
+            .map_err(|x| x.to_string())
+            .and_then(TimingsHistory::try_from)
+            .unwrap_or_default()
+    }
+
+    /// Append a snapshot of `timings`, taken now.
+    pub fn push(&mut self, timings: Timings) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.runs.push(TimingsSnapshot { timestamp, timings });
+    }
+
+    /// Total run time of a day in every snapshot that contains it, as `(timestamp, nanos)`.
+    pub fn for_day(&self, day: Day) -> Vec<(u64, f64)> {
+        self.runs
+            .iter()
+            .filter_map(|run| {
+                run.timings
+                    .data
+                    .iter()
+                    .find(|t| t.day == day)
+                    .map(|t| (run.timestamp, t.total_nanos))
+            })
+            .collect()
+    }
+}
+
+impl From<TimingsHistory> for JsonValue {
+    fn from(value: TimingsHistory) -> Self {
+        let runs = value
+            .runs
+            .into_iter()
+            .map(|run| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                #[allow(clippy::cast_precision_loss)]
+                map.insert("timestamp".into(), JsonValue::Number(run.timestamp as f64));
+                map.insert("timings".into(), JsonValue::from(run.timings));
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert("runs".into(), JsonValue::Array(runs));
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for TimingsHistory {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_runs = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("runs")
+            .ok_or("expected JSON document to have key `runs`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.runs` to be an array.")?;
+
+        let runs = json_runs
+            .iter()
+            .map(|run| {
+                let run = run
+                    .get::<HashMap<String, JsonValue>>()
+                    .ok_or("Expected run to be a JSON object.")?;
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let timestamp = run
+                    .get("timestamp")
+                    .and_then(|v| v.get::<f64>().copied())
+                    .ok_or("Expected run.timestamp to be a number.")?
+                    as u64;
+
+                let timings = run
+                    .get("timings")
+                    .ok_or("Expected run to have key `timings`.")
+                    .map_err(String::from)
+                    .and_then(Timings::try_from)?;
+
+                Ok::<_, String>(TimingsSnapshot { timestamp, timings })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(TimingsHistory { runs })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 #[cfg(feature = "test_lib")]
 mod tests {
     use crate::day;
@@ -245,6 +374,45 @@ mod tests {
         }
     }
 
+    mod history {
+        use crate::{
+            day,
+            template::timings::{Timings, TimingsHistory, TimingsSnapshot},
+        };
+
+        use super::get_mock_timings;
+
+        #[test]
+        fn handles_json_history() {
+            let json = r#"{ "runs": [{ "timestamp": 1733000000, "timings": { "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000 }] } }] }"#.to_string();
+            let history = TimingsHistory::try_from(json).unwrap();
+            assert_eq!(history.runs.len(), 1);
+            assert_eq!(history.runs[0].timestamp, 1733000000);
+            assert_eq!(history.for_day(day!(1)), vec![(1733000000, 1_000_000_f64)]);
+        }
+
+        #[test]
+        fn roundtrips_history() {
+            let history = TimingsHistory {
+                runs: vec![
+                    TimingsSnapshot {
+                        timestamp: 1,
+                        timings: get_mock_timings(),
+                    },
+                    TimingsSnapshot {
+                        timestamp: 2,
+                        timings: Timings::default(),
+                    },
+                ],
+            };
+            let json = tinyjson::JsonValue::from(history).stringify().unwrap();
+            let history = TimingsHistory::try_from(json).unwrap();
+            assert_eq!(history.runs.len(), 2);
+            assert_eq!(history.for_day(day!(2)), vec![(1, 7e+10)]);
+            assert_eq!(history.for_day(day!(3)), vec![]);
+        }
+    }
+
     mod serialization {
         use super::get_mock_timings;
         use std::collections::HashMap;
@@ -285,7 +453,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -299,7 +467,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -313,7 +481,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 